@@ -0,0 +1,84 @@
+//! Procedural macros for [reportify](https://docs.rs/reportify).
+//!
+//! Use the macros through their re-exports in the `reportify` crate.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{ItemFn, ReturnType, Token, parse_macro_input};
+
+/// Arguments of the `context` attribute.
+struct ContextArgs {
+    /// Format the context eagerly, before running the function body.
+    eager: bool,
+    /// Format string and arguments.
+    format: proc_macro2::TokenStream,
+}
+
+impl Parse for ContextArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let eager = input.peek(Token![move]);
+        if eager {
+            input.parse::<Token![move]>()?;
+            input.parse::<Token![,]>()?;
+        }
+        let format = input.parse()?;
+        Ok(Self { eager, format })
+    }
+}
+
+/// Attach context to any report escaping the annotated function.
+///
+/// The function must return `Result<T, Report<E>>`. The context is given as a format
+/// string with arguments, like for [`format!`], and is attached with the location of
+/// the attribute. By default, the context is only formatted when an error occurs, after
+/// the function body has run. In case the body moves any of the values used for
+/// formatting, use `move` as the first argument to format the context eagerly.
+///
+/// ```ignore
+/// #[reportify::context("loading project at {path:?}")]
+/// fn load_project(path: &Path) -> Result<Project, Report<LoadError>> {
+///     ...
+/// }
+///
+/// #[reportify::context(move, "consuming {name}")]
+/// fn consume(name: String) -> Result<(), Report<ConsumeError>> {
+///     ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn context(args: TokenStream, item: TokenStream) -> TokenStream {
+    let ContextArgs { eager, format } = parse_macro_input!(args as ContextArgs);
+    let mut function = parse_macro_input!(item as ItemFn);
+    let ReturnType::Type(_, output) = &function.sig.output else {
+        return syn::Error::new_spanned(
+            &function.sig,
+            "function must return `Result<T, Report<E>>`",
+        )
+        .to_compile_error()
+        .into();
+    };
+    let block = &function.block;
+    let result = if function.sig.asyncness.is_some() {
+        quote! { async #block.await }
+    } else {
+        quote! { (|| -> #output #block)() }
+    };
+    let body = if eager {
+        quote! {{
+            let __reportify_context = ::reportify::__private::format!(#format);
+            let __reportify_result: #output = #result;
+            ::reportify::ResultExt::context(__reportify_result, __reportify_context)
+        }}
+    } else {
+        quote! {{
+            let __reportify_result: #output = #result;
+            ::reportify::ResultExt::context(
+                __reportify_result,
+                || ::reportify::__private::format!(#format),
+            )
+        }}
+    };
+    *function.block = syn::parse_quote!(#body);
+    quote!(#function).into()
+}
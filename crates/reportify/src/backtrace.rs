@@ -0,0 +1,440 @@
+//! Parsing, filtering, and rendering of backtraces.
+
+use std::backtrace::Backtrace;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Globally installed backtrace filter.
+static FILTER: RwLock<Option<BacktraceFilter>> = RwLock::new(None);
+
+/// Crates of the Rust standard library.
+const STD_CRATES: &[&str] = &["std", "core", "alloc", "test", "__rustc"];
+
+/// Crates of async runtimes whose frames are collapsed.
+const ASYNC_RUNTIME_CRATES: &[&str] = &[
+    "tokio",
+    "futures",
+    "futures_core",
+    "futures_util",
+    "futures_executor",
+    "async_std",
+    "async_executor",
+    "async_task",
+    "smol",
+];
+
+/// Frame of a backtrace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Index of the frame in the original backtrace.
+    index: usize,
+    /// Demangled symbol name.
+    symbol: String,
+    /// Source location of the frame, if known.
+    location: Option<FrameLocation>,
+}
+
+impl Frame {
+    /// Index of the frame in the original backtrace.
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Demangled symbol name.
+    #[must_use]
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Source file of the frame, if known.
+    #[must_use]
+    pub fn file(&self) -> Option<&str> {
+        self.location
+            .as_ref()
+            .map(|location| location.file.as_str())
+    }
+
+    /// Source line of the frame, if known.
+    #[must_use]
+    pub fn line(&self) -> Option<u32> {
+        self.location.as_ref().map(|location| location.line)
+    }
+
+    /// Source column of the frame, if known.
+    #[must_use]
+    pub fn column(&self) -> Option<u32> {
+        self.location.as_ref().map(|location| location.column)
+    }
+
+    /// Name of the crate the frame belongs to, if any.
+    ///
+    /// Frames of foreign code, e.g., the C runtime, do not belong to any crate.
+    #[must_use]
+    pub fn crate_name(&self) -> Option<&str> {
+        let mut path = self
+            .symbol
+            .trim_start_matches(['<', '&', '*'])
+            .trim_start_matches("mut ")
+            .trim_start_matches("dyn ");
+        // For qualified paths like `<T as path::Trait>::method`, where `T` is a generic
+        // parameter or primitive type, the crate is given by the trait's path.
+        if let Some((self_type, trait_path)) = path.split_once(" as ")
+            && !self_type.contains("::")
+        {
+            path = trait_path;
+        }
+        let (name, _) = path.split_once("::")?;
+        Some(name)
+    }
+
+    /// Classify the frame.
+    fn kind(&self, filter: &BacktraceFilter) -> FrameKind {
+        let Some(crate_name) = self.crate_name() else {
+            return FrameKind::Std;
+        };
+        if STD_CRATES.contains(&crate_name) {
+            FrameKind::Std
+        } else if crate_name == "reportify" {
+            FrameKind::Reportify
+        } else if ASYNC_RUNTIME_CRATES.contains(&crate_name) {
+            FrameKind::AsyncRuntime
+        } else if filter
+            .hidden_crates
+            .iter()
+            .any(|hidden| hidden == crate_name)
+        {
+            FrameKind::Hidden
+        } else {
+            FrameKind::Application
+        }
+    }
+}
+
+/// Source location of a frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FrameLocation {
+    /// Source file.
+    file: String,
+    /// Source line.
+    line: u32,
+    /// Source column.
+    column: u32,
+}
+
+/// Kind of a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameKind {
+    /// Frame of the standard library or foreign runtime code.
+    Std,
+    /// Frame of this crate.
+    Reportify,
+    /// Frame of an async runtime.
+    AsyncRuntime,
+    /// Frame of a crate that has been explicitly hidden.
+    Hidden,
+    /// Frame of the application.
+    Application,
+}
+
+/// Parse the frames of the given backtrace.
+///
+/// Returns an empty vector in case the backtrace has not been captured.
+#[must_use]
+pub fn frames(backtrace: &Backtrace) -> Vec<Frame> {
+    parse_frames(&backtrace.to_string())
+}
+
+/// Parse frames from the textual representation of a backtrace.
+fn parse_frames(backtrace: &str) -> Vec<Frame> {
+    let mut frames = Vec::<Frame>::new();
+    for line in backtrace.lines() {
+        let line = line.trim();
+        if let Some(location) = line.strip_prefix("at ") {
+            if let Some(frame) = frames.last_mut() {
+                frame.location = parse_location(location);
+            }
+        } else if let Some((index, symbol)) = line.split_once(": ")
+            && let Ok(index) = index.parse()
+        {
+            frames.push(Frame {
+                index,
+                symbol: symbol.to_owned(),
+                location: None,
+            });
+        }
+    }
+    frames
+}
+
+/// Parse a location of the form `file:line:column`.
+fn parse_location(location: &str) -> Option<FrameLocation> {
+    let mut parts = location.rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let line = parts.next()?.parse().ok()?;
+    let file = parts.next()?.to_owned();
+    Some(FrameLocation { file, line, column })
+}
+
+/// Filter for the frames of a backtrace.
+///
+/// By default, frames of the standard library and of this crate are hidden, frames of
+/// async runtimes are collapsed, and paths are shortened relative to the current working
+/// directory.
+///
+/// The filter is configured globally via [`BacktraceFilter::install`].
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct BacktraceFilter {
+    /// Hide frames of the standard library.
+    hide_std: bool,
+    /// Hide frames of this crate.
+    hide_reportify: bool,
+    /// Collapse consecutive frames of async runtimes.
+    collapse_async_runtime: bool,
+    /// Crates whose frames are hidden.
+    hidden_crates: Vec<String>,
+    /// Root of the workspace to which paths are shortened.
+    workspace_root: Option<PathBuf>,
+}
+
+impl BacktraceFilter {
+    /// Create a new filter with the default settings.
+    pub fn new() -> Self {
+        Self {
+            hide_std: true,
+            hide_reportify: true,
+            collapse_async_runtime: true,
+            hidden_crates: Vec::new(),
+            workspace_root: None,
+        }
+    }
+
+    /// Create a filter that shows all frames verbatim.
+    pub fn verbatim() -> Self {
+        Self {
+            hide_std: false,
+            hide_reportify: false,
+            collapse_async_runtime: false,
+            hidden_crates: Vec::new(),
+            workspace_root: None,
+        }
+    }
+
+    /// Set whether frames of the standard library are hidden.
+    pub fn hide_std(mut self, hide: bool) -> Self {
+        self.hide_std = hide;
+        self
+    }
+
+    /// Set whether frames of this crate are hidden.
+    pub fn hide_reportify(mut self, hide: bool) -> Self {
+        self.hide_reportify = hide;
+        self
+    }
+
+    /// Set whether consecutive frames of async runtimes are collapsed.
+    pub fn collapse_async_runtime(mut self, collapse: bool) -> Self {
+        self.collapse_async_runtime = collapse;
+        self
+    }
+
+    /// Hide the frames of the given crate.
+    pub fn hide_crate(mut self, name: impl Into<String>) -> Self {
+        self.hidden_crates.push(name.into());
+        self
+    }
+
+    /// Set the root of the workspace to which paths are shortened.
+    ///
+    /// Defaults to the current working directory.
+    pub fn workspace_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.workspace_root = Some(root.into());
+        self
+    }
+
+    /// Install the filter globally.
+    pub fn install(self) {
+        *FILTER
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(self);
+    }
+
+    /// Globally installed filter.
+    pub fn current() -> Self {
+        FILTER
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// Check whether frames of the given kind are hidden.
+    fn is_hidden(&self, kind: FrameKind) -> bool {
+        match kind {
+            FrameKind::Std => self.hide_std,
+            FrameKind::Reportify => self.hide_reportify,
+            FrameKind::Hidden => true,
+            FrameKind::AsyncRuntime | FrameKind::Application => false,
+        }
+    }
+
+    /// Shorten the given path.
+    fn shorten_path<'p>(&self, path: &'p str, cwd: Option<&Path>) -> &'p str {
+        if let Some(root) = self.workspace_root.as_deref().or(cwd)
+            && let Ok(relative) = Path::new(path).strip_prefix(root)
+            && let Some(relative) = relative.to_str()
+        {
+            return relative;
+        }
+        if let Some(relative) = path.strip_prefix("./") {
+            return relative;
+        }
+        // Paths into the standard library, e.g., `/rustc/<commit>/library/std/...`.
+        if let Some(rest) = path.strip_prefix("/rustc/")
+            && let Some((_, relative)) = rest.split_once('/')
+        {
+            return relative;
+        }
+        // Paths into the Cargo registry, e.g., `.../registry/src/<index>/tokio-1.0.0/...`.
+        if let Some((_, rest)) = path.split_once("/registry/src/")
+            && let Some((_, relative)) = rest.split_once('/')
+        {
+            return relative;
+        }
+        path
+    }
+
+    /// Render the frames of the given backtrace.
+    pub(crate) fn render<W: fmt::Write>(&self, f: &mut W, backtrace: &Backtrace) -> fmt::Result {
+        self.render_frames(f, &frames(backtrace))
+    }
+
+    /// Render the frames of the given textual representation of a backtrace.
+    #[cfg(feature = "serde")]
+    pub(crate) fn render_text<W: fmt::Write>(&self, f: &mut W, backtrace: &str) -> fmt::Result {
+        self.render_frames(f, &parse_frames(backtrace))
+    }
+
+    /// Render the given frames.
+    fn render_frames<W: fmt::Write>(&self, f: &mut W, frames: &[Frame]) -> fmt::Result {
+        let cwd = std::env::current_dir().ok();
+        let mut first_application_frame = true;
+        let mut hidden = 0;
+        let mut collapsed = 0;
+        for frame in frames {
+            let kind = frame.kind(self);
+            if self.is_hidden(kind) {
+                hidden += 1;
+                continue;
+            }
+            if kind == FrameKind::AsyncRuntime && self.collapse_async_runtime {
+                collapsed += 1;
+                continue;
+            }
+            if collapsed > 0 {
+                writeln!(f, "      … {collapsed} async runtime frames")?;
+                collapsed = 0;
+            }
+            let marker = if kind == FrameKind::Application && first_application_frame {
+                first_application_frame = false;
+                "→"
+            } else {
+                " "
+            };
+            writeln!(f, "{marker}{:>4}: {}", frame.index, frame.symbol)?;
+            if let Some(location) = &frame.location {
+                let file = self.shorten_path(&location.file, cwd.as_deref());
+                writeln!(
+                    f,
+                    "          at {file}:{}:{}",
+                    location.line, location.column
+                )?;
+            }
+        }
+        if collapsed > 0 {
+            writeln!(f, "      … {collapsed} async runtime frames")?;
+        }
+        if hidden > 0 {
+            writeln!(f, "      ({hidden} frames hidden)")?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for BacktraceFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BacktraceFilter, FrameKind, parse_frames};
+
+    const BACKTRACE: &str = "   0: reportify::ReportContext::capture
+             at ./crates/reportify/src/lib.rs:170:25
+   1: app::load
+             at ./src/load.rs:10:5
+   2: tokio::runtime::park::CachedParkThread::block_on
+             at /home/user/.cargo/registry/src/index.crates.io-6f17d22bba15001f/tokio-1.45.0/src/runtime/park.rs:284:60
+   3: <&dyn core::ops::function::Fn<()> as core::ops::function::FnOnce<()>>::call_once
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/ops/function.rs:287:21
+   4: main
+   5: _start
+   6: <E as reportify::Reportify<reportify::Report<E>>>::report
+";
+
+    #[test]
+    fn test_parse_frames() {
+        let frames = parse_frames(BACKTRACE);
+        assert_eq!(frames.len(), 7);
+        assert_eq!(frames[1].symbol(), "app::load");
+        assert_eq!(frames[1].file(), Some("./src/load.rs"));
+        assert_eq!(frames[1].line(), Some(10));
+        assert_eq!(frames[1].column(), Some(5));
+        assert_eq!(frames[3].crate_name(), Some("core"));
+        assert_eq!(frames[4].file(), None);
+        let filter = BacktraceFilter::new().hide_crate("app");
+        let kinds = frames
+            .iter()
+            .map(|frame| frame.kind(&filter))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                FrameKind::Reportify,
+                FrameKind::Hidden,
+                FrameKind::AsyncRuntime,
+                FrameKind::Std,
+                FrameKind::Std,
+                FrameKind::Std,
+                FrameKind::Reportify,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shorten_path() {
+        let filter = BacktraceFilter::new().workspace_root("/home/user/project");
+        assert_eq!(
+            filter.shorten_path("/home/user/project/src/main.rs", None),
+            "src/main.rs"
+        );
+        assert_eq!(
+            filter.shorten_path(
+                "/home/user/.cargo/registry/src/index.crates.io-6f17d22bba15001f/tokio-1.45.0/src/lib.rs",
+                None
+            ),
+            "tokio-1.45.0/src/lib.rs"
+        );
+        assert_eq!(
+            filter.shorten_path(
+                "/rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/lib.rs",
+                None
+            ),
+            "library/core/src/lib.rs"
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Bug reports for unexpected errors.
+
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{self, Write as _};
+use std::path::PathBuf;
+
+use crate::render::code_block;
+use crate::{Error, Report, ResultExt};
+
+/// Maximum length of prefilled issue URLs.
+///
+/// Longer URLs are rejected by some browsers and issue trackers.
+const MAX_URL_LEN: usize = 8000;
+
+/// Fragments of command line flags whose values are redacted.
+const SENSITIVE_FLAGS: &[&str] = &["password", "passwd", "secret", "token", "key", "auth"];
+
+/// Placeholder for redacted values.
+const REDACTED: &str = "<redacted>";
+
+/// Maximum number of attempts to create a temporary file with a fresh name.
+const MAX_TEMP_FILE_ATTEMPTS: usize = 16;
+
+/// Bug report for an unexpected error.
+///
+/// Bundles a rendered report with information about the environment, i.e., the version
+/// of the application, the operating system and architecture, the version of Rust, the
+/// sanitized command line arguments, and an allowlist of environment variables. The
+/// bug report is rendered as Markdown, which can be written to a temporary file or
+/// used to prefill an issue:
+///
+/// ```ignore
+/// if let Err(report) = run() {
+///     let bug_report = BugReport::new(&report)
+///         .with_app(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+///         .with_env_var("RUST_LOG")
+///         .with_issue_url("https://github.com/org/repo/issues/new");
+///     if let Ok(path) = bug_report.write_to_temp_file() {
+///         eprintln!("Bug report written to {}.", path.display());
+///     }
+///     if let Some(url) = bug_report.issue_url() {
+///         eprintln!("Please file an issue: {url}");
+///     }
+/// }
+/// ```
+///
+/// Values of command line flags with names indicating secrets, e.g., `--token`, are
+/// redacted, and the home directory of the user is replaced with `~`. Environment
+/// variables are only included if they have been explicitly allowed.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct BugReport {
+    title: String,
+    report: String,
+    app: Option<(String, String)>,
+    rust_version: Option<String>,
+    args: Vec<OsString>,
+    env_vars: Vec<String>,
+    issue_url: Option<String>,
+}
+
+impl BugReport {
+    /// Create a bug report for the given report.
+    pub fn new<E: Error>(report: &Report<E>) -> Self {
+        let report = report.to_string();
+        let title = report
+            .lines()
+            .next()
+            .unwrap_or("unexpected error")
+            .to_owned();
+        Self {
+            title,
+            report,
+            app: None,
+            rust_version: None,
+            args: std::env::args_os().collect(),
+            env_vars: Vec::new(),
+            issue_url: None,
+        }
+    }
+
+    /// Set the name and version of the application.
+    pub fn with_app(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.app = Some((name.into(), version.into()));
+        self
+    }
+
+    /// Set the version of Rust the application has been built with.
+    ///
+    /// The version is not detected automatically, as it is only known when building the
+    /// application, e.g., by running `rustc --version` in its build script.
+    pub fn with_rust_version(mut self, version: impl Into<String>) -> Self {
+        self.rust_version = Some(version.into());
+        self
+    }
+
+    /// Set the title of the bug report.
+    ///
+    /// Defaults to the first line of the rendered report.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the command line arguments, including the program name.
+    ///
+    /// Defaults to the arguments of the current process.
+    pub fn with_args<I, A>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<OsString>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Include the environment variable with the given name.
+    pub fn with_env_var(mut self, name: impl Into<String>) -> Self {
+        self.env_vars.push(name.into());
+        self
+    }
+
+    /// Set the URL for creating new issues, e.g., `https://github.com/org/repo/issues/new`.
+    ///
+    /// The URL may already contain query parameters, e.g., to select an issue template.
+    pub fn with_issue_url(mut self, url: impl Into<String>) -> Self {
+        self.issue_url = Some(url.into());
+        self
+    }
+
+    /// Title of the bug report.
+    #[must_use]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Render the bug report as Markdown.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        self.render(true)
+    }
+
+    /// Write the bug report as Markdown to a new temporary file and return its path.
+    ///
+    /// The file is created with a random name and never replaces an existing file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be created or written.
+    pub fn write_to_temp_file(&self) -> Result<PathBuf, Report<io::Error>> {
+        let name = self.app.as_ref().map_or("bug-report", |(name, _)| name);
+        let directory = std::env::temp_dir();
+        let mut attempt = 1;
+        let (path, mut file) = loop {
+            let suffix = RandomState::new().hash_one(attempt);
+            let path = directory.join(format!("{name}-bug-report-{suffix:016x}.md"));
+            match crate::fs::create_new(&path) {
+                Ok(file) => break (path, file),
+                Err(report)
+                    if report.error().kind() == io::ErrorKind::AlreadyExists
+                        && attempt < MAX_TEMP_FILE_ATTEMPTS =>
+                {
+                    attempt += 1;
+                }
+                Err(report) => return Err(report),
+            }
+        };
+        file.write_all(self.to_markdown().as_bytes())
+            .context(format!("writing file `{}`", path.display()))?;
+        Ok(path)
+    }
+
+    /// URL for creating an issue prefilled with the bug report, if an issue URL is set.
+    ///
+    /// In case the rendered report is too long, it is omitted from the body and the user
+    /// is asked to attach the bug report file instead.
+    #[must_use]
+    pub fn issue_url(&self) -> Option<String> {
+        let base = self.issue_url.as_deref()?;
+        let separator = if base.contains('?') { '&' } else { '?' };
+        let url = |body: &str| {
+            format!(
+                "{base}{separator}title={}&body={}",
+                percent_encode(&self.title),
+                percent_encode(body)
+            )
+        };
+        let full = url(&self.render(true));
+        if full.len() <= MAX_URL_LEN {
+            Some(full)
+        } else {
+            Some(url(&self.render(false)))
+        }
+    }
+
+    /// Render the bug report as Markdown, optionally omitting the rendered report.
+    fn render(&self, include_report: bool) -> String {
+        let home = home_dir();
+        let sanitize = |value: &str| match &home {
+            Some(home) => value.replace(home.as_str(), "~"),
+            None => value.to_owned(),
+        };
+        let mut markdown = String::new();
+        markdown.push_str("## Error\n\n");
+        if include_report {
+            push_code_block(&mut markdown, &sanitize(&self.report));
+        } else {
+            markdown.push_str(
+                "The error report is too long to be included here. \
+                 Please attach the bug report file.\n",
+            );
+        }
+        markdown.push_str("\n## Environment\n\n");
+        if let Some((name, version)) = &self.app {
+            let _ = writeln!(markdown, "- Version: {name} {version}");
+        }
+        let _ = writeln!(
+            markdown,
+            "- OS: {} ({})",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        if let Some(version) = &self.rust_version {
+            let _ = writeln!(markdown, "- Rust: {version}");
+        }
+        if !self.args.is_empty() {
+            markdown.push_str("\n## Command Line\n\n");
+            push_code_block(&mut markdown, &sanitize(&sanitize_args(&self.args)));
+        }
+        let env_vars = self
+            .env_vars
+            .iter()
+            .filter_map(|name| Some(format!("{name}={}", std::env::var(name).ok()?)))
+            .collect::<Vec<_>>();
+        if !env_vars.is_empty() {
+            markdown.push_str("\n## Environment Variables\n\n");
+            push_code_block(&mut markdown, &sanitize(&env_vars.join("\n")));
+        }
+        markdown
+    }
+}
+
+/// Home directory of the current user, if known.
+fn home_dir() -> Option<String> {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .filter(|home| home.len() > 1)
+}
+
+/// Join the command line arguments, redacting values of sensitive flags.
+fn sanitize_args(args: &[OsString]) -> String {
+    let mut sanitized = Vec::with_capacity(args.len());
+    let mut redact_next = false;
+    for arg in args {
+        let arg = arg.to_string_lossy();
+        if redact_next {
+            sanitized.push(REDACTED.to_owned());
+            redact_next = false;
+            continue;
+        }
+        let Some(flag) = arg.strip_prefix('-') else {
+            sanitized.push(arg.into_owned());
+            continue;
+        };
+        let name = flag.split_once('=').map_or(flag, |(name, _)| name);
+        let is_sensitive = SENSITIVE_FLAGS
+            .iter()
+            .any(|fragment| name.to_ascii_lowercase().contains(fragment));
+        if !is_sensitive {
+            sanitized.push(arg.into_owned());
+        } else if name.len() < flag.len() {
+            sanitized.push(format!("-{name}={REDACTED}"));
+        } else {
+            sanitized.push(arg.into_owned());
+            redact_next = true;
+        }
+    }
+    sanitized.join(" ")
+}
+
+/// Append a fenced code block.
+fn push_code_block(markdown: &mut String, content: &str) {
+    markdown.push_str(&code_block(content));
+    markdown.push('\n');
+}
+
+/// Percent-encode the given value for use in a URL query.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use super::{BugReport, percent_encode, push_code_block, sanitize_args};
+    use crate::{Report, ResultExt};
+
+    #[test]
+    fn test_sanitize_args() {
+        let args = ["app", "--token", "abc", "--api-key=xyz", "-v", "build"].map(OsString::from);
+        assert_eq!(
+            sanitize_args(&args),
+            "app --token <redacted> --api-key=<redacted> -v build"
+        );
+    }
+
+    #[test]
+    fn test_push_code_block() {
+        let mut markdown = String::new();
+        push_code_block(&mut markdown, "running `cargo build`\n````");
+        assert_eq!(markdown, "`````text\nrunning `cargo build`\n````\n`````\n");
+    }
+
+    #[test]
+    fn test_issue_url() {
+        assert_eq!(percent_encode("a b&c/ä"), "a%20b%26c%2F%C3%A4");
+        let report: Report<std::io::Error> = Err::<(), _>(std::io::Error::other("disk on fire"))
+            .context("writing output")
+            .expect_err("should fail");
+        let bug_report = BugReport::new(&report)
+            .with_app("app", "1.2.3")
+            .with_rust_version("rustc 1.89.0")
+            .with_args(["app", "--password", "hunter2"])
+            .with_issue_url("https://example.com/issues/new?template=bug.md");
+        let markdown = bug_report.to_markdown();
+        assert!(markdown.contains("- Version: app 1.2.3\n"));
+        assert!(markdown.contains("- Rust: rustc 1.89.0\n"));
+        assert!(markdown.contains(": writing output\n"));
+        assert!(!markdown.contains("hunter2"));
+        let url = bug_report.issue_url().expect("should have issue URL");
+        assert!(url.starts_with(
+            "https://example.com/issues/new?template=bug.md&title=disk%20on%20fire&body="
+        ));
+    }
+
+    #[test]
+    fn test_write_to_temp_file() {
+        let report: Report<std::io::Error> = Err::<(), _>(std::io::Error::other("disk on fire"))
+            .context("writing output")
+            .expect_err("should fail");
+        let bug_report = BugReport::new(&report).with_app("reportify-test", "1.2.3");
+        let first = bug_report.write_to_temp_file().expect("should write");
+        let second = bug_report.write_to_temp_file().expect("should write");
+        assert_ne!(first, second);
+        let markdown = std::fs::read_to_string(&first).expect("should read");
+        assert_eq!(markdown, bug_report.to_markdown());
+        std::fs::remove_file(first).expect("should remove");
+        std::fs::remove_file(second).expect("should remove");
+    }
+}
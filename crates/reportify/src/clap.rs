@@ -0,0 +1,283 @@
+//! Integration with [`clap`](https://docs.rs/clap) for command line argument errors.
+//!
+//! Errors produced by clap are rendered in clap's own style. [`ArgumentError::report`]
+//! converts them into reports, keeping clap's tips and usage as suggestions, such
+//! that they are rendered like any other report:
+//!
+//! ```
+//! # use clap4 as clap;
+//! use reportify::Exit;
+//! use reportify::clap::ArgumentError;
+//!
+//! fn main() -> Exit<ArgumentError> {
+//!     let command = clap::Command::new("app").arg(clap::Arg::new("port").long("port"));
+//!     let matches = match command.try_get_matches_from(["app", "--port", "8080"]) {
+//!         Ok(matches) => matches,
+//!         Err(error) => return Exit::from(ArgumentError::report(error)),
+//!     };
+//!     println!("port: {:?}", matches.get_one::<String>("port"));
+//!     Exit::from(Ok(()))
+//! }
+//! ```
+//!
+//! For parsers derived with clap's derive API, [`parse`] parses the arguments of the
+//! current process and creates the report. Like for clap itself, the process exits
+//! with code `2` for invalid arguments.
+//!
+//! In the other direction, [`value_parser`] adapts parsers returning reports for use
+//! as clap value parsers, turning the suggestions of the reports into clap tips.
+
+use std::ffi::OsStr;
+use std::fmt::{self, Display};
+
+use clap4::builder::{StyledStr, TypedValueParser};
+use clap4::error::{ContextKind, ContextValue, ErrorKind};
+use clap4::{Arg, Command};
+
+use crate::i18n::ErrorMessage;
+use crate::{Error, Help, Report, ReportContext, ReportItem};
+
+/// Invalid command line arguments.
+#[derive(Debug)]
+pub struct ArgumentError {
+    error: clap4::Error,
+    message: String,
+}
+
+impl ArgumentError {
+    /// Create a report for the given clap error.
+    ///
+    /// The tips and the usage included in clap's rendering of the error are attached
+    /// as suggestions.
+    #[track_caller]
+    pub fn report(error: clap4::Error) -> Report<Self> {
+        let rendered = error.render().to_string();
+        let mut paragraphs = rendered.split("\n\n").map(str::trim_end);
+        let message = paragraphs
+            .next()
+            .map(|message| message.strip_prefix("error: ").unwrap_or(message))
+            .unwrap_or_default()
+            .to_owned();
+        let mut help = Vec::new();
+        for paragraph in paragraphs.filter(|paragraph| !paragraph.is_empty()) {
+            let tips = paragraph
+                .lines()
+                .filter_map(|line| line.trim_start().strip_prefix("tip: "))
+                .collect::<Vec<_>>();
+            if tips.is_empty() {
+                help.push(paragraph.to_owned());
+            } else {
+                help.extend(tips.into_iter().map(str::to_owned));
+            }
+        }
+        let mut report = Report::new(Self { error, message }, ReportContext::capture());
+        for help in help {
+            report = report.with_context(Help::new(help));
+        }
+        report
+    }
+
+    /// Kind of the error.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        self.error.kind()
+    }
+
+    /// Underlying clap error.
+    #[must_use]
+    pub fn into_inner(self) -> clap4::Error {
+        self.error
+    }
+}
+
+impl Error for ArgumentError {
+    fn message(&self) -> Option<&dyn Display> {
+        Some(&self.message)
+    }
+
+    fn as_std_error(&self) -> Option<&dyn std::error::Error> {
+        Some(&self.error)
+    }
+
+    fn exit_code(&self) -> Option<u8> {
+        u8::try_from(self.error.exit_code()).ok()
+    }
+}
+
+/// Parse the command line arguments of the current process.
+///
+/// Like [`clap::Parser::parse`](https://docs.rs/clap/latest/clap/trait.Parser.html#method.parse),
+/// help and version requests are printed and the process exits.
+///
+/// # Errors
+///
+/// Fails with a report of the clap error if the arguments are invalid.
+#[track_caller]
+pub fn parse<P: clap4::Parser>() -> Result<P, Report<ArgumentError>> {
+    match P::try_parse() {
+        Ok(parsed) => Ok(parsed),
+        Err(error) if !error.use_stderr() => error.exit(),
+        Err(error) => Err(ArgumentError::report(error)),
+    }
+}
+
+/// Adapt a parser returning reports for use as a clap value parser.
+///
+/// The message of the report becomes the reason why the value is invalid and the
+/// suggestions of the report become tips:
+///
+/// ```
+/// # use clap4 as clap;
+/// # use reportify::{Help, Report, ResultExt};
+/// fn parse_port(value: &str) -> Result<u16, Report<std::num::ParseIntError>> {
+///     value
+///         .parse::<u16>()
+///         .context(Help::new("use a port number between 0 and 65535"))
+/// }
+///
+/// let arg = clap::Arg::new("port")
+///     .long("port")
+///     .value_parser(reportify::clap::value_parser(parse_port));
+/// ```
+pub fn value_parser<T, E, F>(parse: F) -> ValueParser<F>
+where
+    F: Fn(&str) -> Result<T, Report<E>> + Clone + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    E: Error,
+{
+    ValueParser(parse)
+}
+
+/// Clap value parser created by [`value_parser`].
+#[derive(Debug, Clone)]
+pub struct ValueParser<F>(F);
+
+impl<T, E, F> TypedValueParser for ValueParser<F>
+where
+    F: Fn(&str) -> Result<T, Report<E>> + Clone + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    E: Error,
+{
+    type Value = T;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<T, clap4::Error> {
+        let Some(value_str) = value.to_str() else {
+            return Err(clap4::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd));
+        };
+        let report = match (self.0)(value_str) {
+            Ok(parsed) => return Ok(parsed),
+            Err(report) => report,
+        };
+        let reason = InvalidValue(reason(&report));
+        // Let clap construct the error such that it is rendered like other invalid values.
+        let reject = move |_: &str| Err::<(), _>(reason.clone());
+        let mut error = match reject.parse_ref(cmd, arg, value) {
+            Err(error) => error,
+            Ok(()) => clap4::Error::new(ErrorKind::ValueValidation).with_cmd(cmd),
+        };
+        let tips = report
+            .context
+            .items
+            .iter()
+            .filter_map(|entry| match &entry.item {
+                ReportItem::Help(help) => Some(StyledStr::from(help.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !tips.is_empty() {
+            error.insert(ContextKind::Suggested, ContextValue::StyledStrs(tips));
+        }
+        Err(error)
+    }
+}
+
+/// Reason why a value is invalid, i.e., the message of the report or, if the error
+/// does not have a message, the most recently attached context message.
+fn reason<E: Error>(report: &Report<E>) -> String {
+    if let Some(message) = ErrorMessage::of(&report.error) {
+        return message.to_string();
+    }
+    report
+        .context
+        .items
+        .iter()
+        .rev()
+        .find_map(|entry| match &entry.item {
+            ReportItem::Message(message) => Some(message.clone()),
+            ReportItem::Localized(message) => Some(message.to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| "invalid value".to_owned())
+}
+
+/// Reason why a value is invalid, used as source of clap errors.
+#[derive(Debug, Clone)]
+struct InvalidValue(String);
+
+impl Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvalidValue {}
+
+#[cfg(test)]
+mod tests {
+    use std::process::{ExitCode, Termination};
+
+    use clap4::{Arg, Command};
+
+    use super::{ArgumentError, value_parser};
+    use crate::{Exit, Help, Report, ResultExt};
+
+    fn parse_port(value: &str) -> Result<u16, Report<std::num::ParseIntError>> {
+        value
+            .parse::<u16>()
+            .context(Help::new("use a port number between 0 and 65535"))
+    }
+
+    fn command() -> Command {
+        Command::new("app")
+            .arg(
+                Arg::new("port")
+                    .long("port")
+                    .value_parser(value_parser(parse_port)),
+            )
+            .arg(Arg::new("verbose").long("verbose").num_args(0))
+    }
+
+    #[test]
+    fn test_value_parser() {
+        let matches = command()
+            .try_get_matches_from(["app", "--port", "8080"])
+            .expect("should parse");
+        assert_eq!(matches.get_one::<u16>("port"), Some(&8080));
+        let error = command()
+            .try_get_matches_from(["app", "--port", "http"])
+            .expect_err("should fail");
+        let rendered = error.render().to_string();
+        assert!(rendered.starts_with(
+            "error: invalid value 'http' for '--port <port>': invalid digit found in string\n"
+        ));
+        assert!(rendered.contains("tip: use a port number between 0 and 65535\n"));
+    }
+
+    #[test]
+    fn test_argument_error() {
+        let error = command()
+            .try_get_matches_from(["app", "--verbos"])
+            .expect_err("should fail");
+        let report = ArgumentError::report(error);
+        let rendered = report.to_string();
+        assert!(rendered.starts_with("unexpected argument '--verbos' found\n"));
+        assert!(rendered.contains("help: a similar argument exists: '--verbose'\n"));
+        assert!(rendered.contains("help: Usage: app --verbose\n"));
+        assert_eq!(Exit::from(report).report(), ExitCode::from(2));
+    }
+}
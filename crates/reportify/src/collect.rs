@@ -0,0 +1,265 @@
+//! Collection of results reporting all failures.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::panic::Location;
+
+use crate::render::NestedReport;
+use crate::{Error, Report, ReportContext, ReportItem, Severity};
+
+/// Extension trait for iterators over results adding methods for collecting all
+/// failures.
+///
+/// In contrast to collecting into a [`Result`], which stops at the first error, the
+/// methods of this trait consume the entire iterator and aggregate all failures into a
+/// single report with [`Failures`] as error. Each failure is tagged with its index or,
+/// for iterators over pairs of a label and a result, with its label:
+///
+/// ```
+/// # use reportify::{Failures, IteratorExt, Report};
+/// fn parse_all(values: &[&str]) -> Result<Vec<u32>, Report<Failures<std::num::ParseIntError>>> {
+///     values
+///         .iter()
+///         .map(|value| (format!("parsing {value:?}"), value.parse::<u32>()))
+///         .try_collect_all()
+/// }
+///
+/// let report = parse_all(&["1", "x", "3", "y"]).unwrap_err();
+/// assert_eq!(report.error().failures().len(), 2);
+/// ```
+pub trait IteratorExt: Iterator + Sized {
+    /// Collect all values or, if any item fails, report all failures.
+    ///
+    /// # Errors
+    ///
+    /// Fails with a report of all failures if any item fails.
+    #[track_caller]
+    fn try_collect_all<C, E>(self) -> Result<C, Report<Failures<E>>>
+    where
+        Self::Item: Outcome<E>,
+        C: FromIterator<<Self::Item as Outcome<E>>::Value>,
+        E: Error;
+
+    /// Collect all values and report all failures, if any.
+    #[track_caller]
+    #[expect(clippy::type_complexity, reason = "pair of values and failures")]
+    fn partition_reports<E>(
+        self,
+    ) -> (
+        Vec<<Self::Item as Outcome<E>>::Value>,
+        Option<Report<Failures<E>>>,
+    )
+    where
+        Self::Item: Outcome<E>,
+        E: Error;
+}
+
+impl<I: Iterator> IteratorExt for I {
+    #[track_caller]
+    fn try_collect_all<C, E>(self) -> Result<C, Report<Failures<E>>>
+    where
+        Self::Item: Outcome<E>,
+        C: FromIterator<<Self::Item as Outcome<E>>::Value>,
+        E: Error,
+    {
+        match self.partition_reports() {
+            (values, None) => Ok(values.into_iter().collect()),
+            (_, Some(report)) => Err(report),
+        }
+    }
+
+    #[track_caller]
+    fn partition_reports<E>(
+        self,
+    ) -> (
+        Vec<<Self::Item as Outcome<E>>::Value>,
+        Option<Report<Failures<E>>>,
+    )
+    where
+        Self::Item: Outcome<E>,
+        E: Error,
+    {
+        let location = Location::caller();
+        let mut values = Vec::new();
+        let mut failures = Vec::new();
+        let mut labels = Vec::new();
+        let mut total = 0;
+        for (index, item) in self.enumerate() {
+            total += 1;
+            match item.into_result() {
+                Ok(value) => values.push(value),
+                Err((label, mut report)) => {
+                    let label = label.unwrap_or_else(|| format!("item {index}"));
+                    report
+                        .context
+                        .push(location, ReportItem::Message(label.clone()));
+                    failures.push(report);
+                    labels.push(label);
+                }
+            }
+        }
+        if failures.is_empty() {
+            return (values, None);
+        }
+        let failures = Failures {
+            reports: failures,
+            labels,
+            total,
+        };
+        (
+            values,
+            Some(Report::new(failures, ReportContext::capture())),
+        )
+    }
+}
+
+/// Item of an iterator which can be collected with [`IteratorExt`].
+///
+/// Implemented for results and pairs of a label and a result. The error of a result
+/// can be any type convertible into a report, e.g., a [`Report`] itself.
+pub trait Outcome<E> {
+    /// Value of the outcome.
+    type Value;
+
+    /// Convert the outcome into a result with an optional label for the failure.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the label and report of a failed outcome.
+    #[track_caller]
+    fn into_result(self) -> Result<Self::Value, (Option<String>, Report<E>)>;
+}
+
+impl<T, R: Into<Report<E>>, E> Outcome<E> for Result<T, R> {
+    type Value = T;
+
+    #[track_caller]
+    fn into_result(self) -> Result<T, (Option<String>, Report<E>)> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err((None, error.into())),
+        }
+    }
+}
+
+impl<L: Display, T, R: Into<Report<E>>, E> Outcome<E> for (L, Result<T, R>) {
+    type Value = T;
+
+    #[track_caller]
+    fn into_result(self) -> Result<T, (Option<String>, Report<E>)> {
+        match self.1 {
+            Ok(value) => Ok(value),
+            Err(error) => Err((Some(self.0.to_string()), error.into())),
+        }
+    }
+}
+
+/// Failures of collecting an iterator with [`IteratorExt`].
+///
+/// The message summarizes how many items failed. The reports of the failures are
+/// rendered as nested reports titled with the labels of the failed items. The severity
+/// is the highest severity of the failures.
+#[derive(Debug)]
+pub struct Failures<E> {
+    reports: Vec<Report<E>>,
+    /// Labels of the failed items.
+    labels: Vec<String>,
+    total: usize,
+}
+
+impl<E> Failures<E> {
+    /// Reports of the failures, in the order of the items.
+    #[must_use]
+    pub fn failures(&self) -> &[Report<E>] {
+        &self.reports
+    }
+
+    /// Consume the failures and return the reports.
+    #[must_use]
+    pub fn into_failures(self) -> Vec<Report<E>> {
+        self.reports
+    }
+
+    /// Total number of collected items, including successes.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+impl<E: Error> Display for Failures<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} items failed", self.reports.len(), self.total)
+    }
+}
+
+impl<E: Error> Error for Failures<E> {
+    fn message(&self) -> Option<&dyn Display> {
+        Some(self)
+    }
+
+    fn nested_reports(&self) -> Vec<(String, &dyn NestedReport)> {
+        self.labels
+            .iter()
+            .zip(&self.reports)
+            .map(|(label, report)| (format!("Failure of {label}"), report as &dyn NestedReport))
+            .collect()
+    }
+
+    fn severity(&self) -> Severity {
+        self.reports
+            .iter()
+            .map(Report::severity)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::ParseIntError;
+
+    use super::{Failures, IteratorExt};
+    use crate::Report;
+
+    #[test]
+    fn test_collect_all() {
+        let values = ["1", "x", "3", "y"];
+        let result: Result<Vec<u32>, Report<Failures<ParseIntError>>> =
+            values.iter().map(|value| value.parse()).try_collect_all();
+        let report = result.expect_err("should fail");
+        assert_eq!(report.error().failures().len(), 2);
+        assert_eq!(report.error().total(), 4);
+        assert_eq!(report.error().to_string(), "2 of 4 items failed");
+        let rendered = report.to_string();
+        assert!(rendered.starts_with("2 of 4 items failed\n"), "{rendered}");
+        for index in [1, 3] {
+            let title = format!("\nFailure of item {index}:\ninvalid digit");
+            assert_eq!(rendered.matches(&title).count(), 1, "{rendered}");
+            let label = format!(": item {index}\n");
+            assert_eq!(rendered.matches(&label).count(), 1, "{rendered}");
+        }
+        let html = report.render_html();
+        assert_eq!(html.matches("<div class=\"report\">").count(), 3, "{html}");
+        assert!(
+            html.contains(
+                "<details><summary>Failure of item 1</summary>\n<div class=\"report\">\n"
+            ),
+            "{html}"
+        );
+        let (values, report) = values
+            .iter()
+            .map(|value| (format!("parsing {value}"), value.parse::<u32>()))
+            .partition_reports::<ParseIntError>();
+        assert_eq!(values, [1, 3]);
+        let report = report.expect("should report failures");
+        assert!(report.to_string().contains(": parsing y\n"));
+        let result: Result<Vec<u32>, Report<Failures<ParseIntError>>> = ["1", "2"]
+            .iter()
+            .map(|value| value.parse())
+            .try_collect_all();
+        assert_eq!(result.expect("should succeed"), [1, 2]);
+    }
+}
@@ -0,0 +1,278 @@
+//! Deserialization reporting where the input is invalid.
+//!
+//! Errors of deserializers usually only describe the problem and the position in the
+//! input, e.g., `invalid type: string "http", expected u16 at line 3 column 18`. The
+//! functions of this module track the path to the field being deserialized and attach
+//! it to the report together with the name of the input, e.g., the file name. Only for
+//! JSON text deserialized with [`from_json_str`], a snippet of the offending line is
+//! attached as well, as other deserializers do not expose the position of an error:
+//!
+//! ```text
+//! invalid type: string "http", expected u16 at line 3 column 18
+//!
+//! src/config.rs:12:5: path=server.port file=config.json
+//! src/config.rs:12:5: invalid type: string "http", expected u16 at line 3 column 18
+//!
+//! help: config.json:3:18
+//!     3 |     "port": "http"
+//!       |                  ^
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+
+use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+
+use crate::{Fields, Help, Report, ReportContext};
+
+/// Invalid input of a deserializer.
+///
+/// Displays as the error of the deserializer, whose sources are the sources of this
+/// error.
+#[derive(Debug)]
+pub struct DeserializeError {
+    error: Box<dyn StdError + Send + Sync>,
+    path: String,
+    position: Option<(usize, usize)>,
+}
+
+impl DeserializeError {
+    /// Path to the field which failed to deserialize, e.g., `server.port`.
+    ///
+    /// The path is `.` if the error concerns the input as a whole.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// One-based line and column at which the error has been detected, if known.
+    #[must_use]
+    pub fn position(&self) -> Option<(usize, usize)> {
+        self.position
+    }
+
+    /// Error of the deserializer, e.g., a [`serde_json::Error`].
+    #[must_use]
+    pub fn get_ref(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        &*self.error
+    }
+}
+
+impl Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl StdError for DeserializeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+/// Deserialize a value with the given deserializer, reporting the path to the failing
+/// field.
+///
+/// The name of the input, e.g., the name of the file it has been read from, is
+/// included in the report. This works with any format, e.g., TOML or YAML. However, as
+/// deserializers do not expose the text and the position of an error, no snippet of
+/// the offending line is reported and the [position][DeserializeError::position] is
+/// unknown, even for JSON. For JSON text, use [`from_json_str`], which also reports a
+/// snippet.
+///
+/// # Errors
+///
+/// Fails if the deserializer rejects the input.
+#[track_caller]
+pub fn deserialize<'de, T, D>(
+    deserializer: D,
+    name: impl Display,
+) -> Result<T, Report<DeserializeError>>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+    D::Error: Send + Sync + 'static,
+{
+    let error = match serde_path_to_error::deserialize(deserializer) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+    let path = error.path().to_string();
+    let error = DeserializeError {
+        error: Box::new(error.into_inner()),
+        path,
+        position: None,
+    };
+    let fields = Fields::new()
+        .field("path", &error.path)
+        .field("file", &name);
+    Err(Report::new(error, ReportContext::capture()).with_context(fields))
+}
+
+/// Deserialize a value from the given JSON text, reporting the path to the failing
+/// field and a snippet of the offending line.
+///
+/// The name of the text, e.g., the name of the file it has been read from, is
+/// included in the report.
+///
+/// # Errors
+///
+/// Fails if the text is not valid JSON or does not match the type.
+#[track_caller]
+pub fn from_json_str<T: DeserializeOwned>(
+    text: &str,
+    name: impl Display,
+) -> Result<T, Report<DeserializeError>> {
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    let (path, error) = match serde_path_to_error::deserialize(&mut deserializer) {
+        Ok(value) => match deserializer.end() {
+            Ok(()) => return Ok(value),
+            Err(error) => (".".to_owned(), error),
+        },
+        Err(error) => (error.path().to_string(), error.into_inner()),
+    };
+    let position = (error.line() > 0).then(|| (error.line(), error.column()));
+    let error = DeserializeError {
+        error: Box::new(error),
+        path,
+        position,
+    };
+    let fields = Fields::new()
+        .field("path", &error.path)
+        .field("file", &name);
+    let mut report = Report::new(error, ReportContext::capture()).with_context(fields);
+    if let Some((line, column)) = position
+        && let Some(snippet) = snippet(text, line, column)
+    {
+        report = report.with_context(Help::new(format!("{name}:{line}:{column}\n{snippet}")));
+    }
+    Err(report)
+}
+
+/// Snippet of the given line of the text with a marker at the given column.
+fn snippet(text: &str, line: usize, column: usize) -> Option<String> {
+    let content = text.lines().nth(line.checked_sub(1)?)?;
+    let number = line.to_string();
+    let offset = content
+        .char_indices()
+        .take_while(|(index, _)| *index < column.saturating_sub(1))
+        .count();
+    Some(format!(
+        "{number:>width$} | {content}\n{:>width$} | {:>offset$}^",
+        "",
+        "",
+        width = number.len() + 4,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::{DeserializeError, deserialize, from_json_str};
+    use crate::Report;
+
+    #[derive(Debug, Deserialize)]
+    #[expect(dead_code, reason = "only deserialized")]
+    struct Config {
+        server: Server,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[expect(dead_code, reason = "only deserialized")]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    #[test]
+    fn test_from_json_str() {
+        let text =
+            "{\n  \"server\": {\n    \"host\": \"localhost\",\n    \"port\": \"http\"\n  }\n}\n";
+        let report: Report<DeserializeError> =
+            from_json_str::<Config>(text, "config.json").expect_err("should fail");
+        assert_eq!(report.error().path(), "server.port");
+        assert_eq!(report.error().position(), Some((4, 18)));
+        let rendered = report.to_string();
+        assert!(rendered.starts_with("invalid type: string \"http\", expected u16"));
+        assert!(
+            rendered.contains(": path=server.port file=config.json\n"),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains(
+                "help: config.json:4:18\n    4 |     \"port\": \"http\"\n      |                  ^\n"
+            ),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let report: Report<DeserializeError> =
+            from_json_str::<Config>("nope\n", "config.json").expect_err("should fail");
+        assert_eq!(report.error().path(), ".");
+        assert_eq!(report.error().position(), Some((1, 2)));
+        let error = report
+            .error()
+            .get_ref()
+            .downcast_ref::<serde_json::Error>()
+            .expect("should keep the error of the deserializer");
+        assert!(error.is_syntax());
+        let rendered = report.to_string();
+        assert!(rendered.starts_with("expected ident at line 1 column 2\n"));
+        assert!(
+            rendered.contains(": path=. file=config.json\n"),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("help: config.json:1:2\n    1 | nope\n      |  ^\n"),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let text = r#"{"server": {"host": "localhost", "port": -1}}"#;
+        let mut deserializer = serde_json::Deserializer::from_str(text);
+        let report: Report<DeserializeError> =
+            deserialize::<Config, _>(&mut deserializer, "config.json").expect_err("should fail");
+        assert_eq!(report.error().path(), "server.port");
+        assert_eq!(report.error().position(), None);
+        let rendered = report.to_string();
+        assert!(rendered.starts_with("invalid value: integer `-1`, expected u16"));
+        assert!(
+            rendered.contains(": path=server.port file=config.json\n"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("help:"), "{rendered}");
+    }
+
+    #[test]
+    fn test_source() {
+        let reader = std::io::Read::chain(&b"{"[..], FailingReader);
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let report: Report<DeserializeError> =
+            deserialize::<Config, _>(&mut deserializer, "stdin").expect_err("should fail");
+        let error = report
+            .error()
+            .get_ref()
+            .downcast_ref::<serde_json::Error>()
+            .expect("should keep the error of the deserializer");
+        assert_eq!(error.io_error_kind(), Some(std::io::ErrorKind::Other));
+        assert!(
+            report
+                .to_string()
+                .starts_with("pipe closed at line 1 column 1\n")
+        );
+    }
+
+    /// Reader failing on every read.
+    struct FailingReader;
+
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("pipe closed"))
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Failed assertions recorded by the `ensure_*` macros.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::{self, Debug, Display};
+
+/// Maximum length of values which are rendered on a single line.
+const MAX_INLINE_LEN: usize = 60;
+
+/// Maximum number of lines of values which are diffed.
+const MAX_DIFF_LINES: usize = 512;
+
+/// Failed assertion recorded as part of the report context.
+#[derive(Debug)]
+pub(crate) enum Assertion {
+    /// Assertion that two values are equal.
+    Eq { left: String, right: String },
+    /// Assertion that two values are not equal.
+    Ne { left: String, right: String },
+    /// Assertion that a value matches a pattern.
+    Matches {
+        value: String,
+        pattern: &'static str,
+    },
+}
+
+impl Assertion {
+    /// Create a failed assertion that two values are equal.
+    pub(crate) fn eq(left: &dyn Debug, right: &dyn Debug) -> Self {
+        let (left, right) = format_values(left, right);
+        Self::Eq { left, right }
+    }
+
+    /// Create a failed assertion that two values are not equal.
+    pub(crate) fn ne(left: &dyn Debug, right: &dyn Debug) -> Self {
+        let (left, right) = format_values(left, right);
+        Self::Ne { left, right }
+    }
+
+    /// Create a failed assertion that a value matches a pattern.
+    pub(crate) fn matches(value: &dyn Debug, pattern: &'static str) -> Self {
+        let value = format_value(value);
+        Self::Matches { value, pattern }
+    }
+}
+
+/// Format a value using its compact representation, unless it is too long.
+fn format_value(value: &dyn Debug) -> String {
+    let compact = format!("{value:?}");
+    if compact.len() > MAX_INLINE_LEN {
+        format!("{value:#?}")
+    } else {
+        compact
+    }
+}
+
+/// Format both values using the same representation.
+fn format_values(left: &dyn Debug, right: &dyn Debug) -> (String, String) {
+    let compact = (format!("{left:?}"), format!("{right:?}"));
+    if compact.0.len() > MAX_INLINE_LEN || compact.1.len() > MAX_INLINE_LEN {
+        (format!("{left:#?}"), format!("{right:#?}"))
+    } else {
+        compact
+    }
+}
+
+impl Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Assertion::Eq { left, right } => {
+                f.write_str("assertion `left == right` failed")?;
+                if left.contains('\n') || right.contains('\n') {
+                    write_diff(f, left, right)
+                } else {
+                    write_value(f, " left", left)?;
+                    write_value(f, "right", right)
+                }
+            }
+            Assertion::Ne { left, right } => {
+                f.write_str("assertion `left != right` failed")?;
+                write_value(f, " left", left)?;
+                write_value(f, "right", right)
+            }
+            Assertion::Matches { value, pattern } => {
+                write!(f, "assertion `value matches {pattern}` failed")?;
+                write_value(f, "value", value)
+            }
+        }
+    }
+}
+
+/// Write a labeled value, indenting continuation lines.
+fn write_value(f: &mut fmt::Formatter<'_>, label: &str, value: &str) -> fmt::Result {
+    let mut lines = value.lines();
+    write!(f, "\n  {label}: {}", lines.next().unwrap_or_default())?;
+    for line in lines {
+        write!(f, "\n         {line}")?;
+    }
+    Ok(())
+}
+
+/// Write a line diff of both values.
+fn write_diff(f: &mut fmt::Formatter<'_>, left: &str, right: &str) -> fmt::Result {
+    let left = left.lines().collect::<Vec<_>>();
+    let right = right.lines().collect::<Vec<_>>();
+    if left.len() > MAX_DIFF_LINES || right.len() > MAX_DIFF_LINES {
+        write_value(f, " left", &left.join("\n"))?;
+        return write_value(f, "right", &right.join("\n"));
+    }
+    f.write_str("\n  diff (- left, + right):")?;
+    for line in diff_lines(&left, &right) {
+        match line {
+            DiffLine::Both(line) => write!(f, "\n      {line}")?,
+            DiffLine::Left(line) => write!(f, "\n    - {line}")?,
+            DiffLine::Right(line) => write!(f, "\n    + {line}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Line of a diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffLine<'s> {
+    /// Line is present in both inputs.
+    Both(&'s str),
+    /// Line is only present in the left input.
+    Left(&'s str),
+    /// Line is only present in the right input.
+    Right(&'s str),
+}
+
+/// Compute a line diff based on the longest common subsequence of both inputs.
+fn diff_lines<'s>(left: &[&'s str], right: &[&'s str]) -> Vec<DiffLine<'s>> {
+    // `lcs[i][j]` is the length of the longest common subsequence of `left[i..]` and
+    // `right[j..]`.
+    let mut lcs = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lcs[i][j] = if left[i] == right[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if left[i] == right[j] {
+            diff.push(DiffLine::Both(left[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Left(left[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Right(right[j]));
+            j += 1;
+        }
+    }
+    diff.extend(left[i..].iter().map(|line| DiffLine::Left(line)));
+    diff.extend(right[j..].iter().map(|line| DiffLine::Right(line)));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Assertion, DiffLine, diff_lines};
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines(&["a", "b", "c"], &["a", "x", "c", "d"]);
+        assert_eq!(
+            diff,
+            [
+                DiffLine::Both("a"),
+                DiffLine::Left("b"),
+                DiffLine::Right("x"),
+                DiffLine::Both("c"),
+                DiffLine::Right("d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_assertion() {
+        assert_eq!(
+            Assertion::eq(&1, &2).to_string(),
+            "assertion `left == right` failed\n   left: 1\n  right: 2"
+        );
+        let left = vec!["a".repeat(30), "b".repeat(30)];
+        let right = vec!["a".repeat(30), "c".repeat(30)];
+        let rendered = Assertion::eq(&left, &right).to_string();
+        assert!(rendered.contains(&format!("\n    -     \"{}\",", "b".repeat(30))));
+        assert!(rendered.contains(&format!("\n    +     \"{}\",", "c".repeat(30))));
+    }
+}
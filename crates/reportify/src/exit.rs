@@ -0,0 +1,78 @@
+//! Termination of the program with a report.
+
+use std::process::{ExitCode, Termination};
+
+use crate::{Error, Report, Severity, render};
+
+/// Result of `main` rendering reports on termination.
+///
+/// Returning a [`Result`] from `main` prints the [`Debug`] representation of the error.
+/// Instead, [`Exit`] prints the rendered report to stderr, colored if supported by the
+/// terminal, and terminates with the [exit code][Error::exit_code] of the error or,
+/// if the error does not specify one, the [exit code][Severity::exit_code] of the
+/// severity of the report:
+///
+/// ```
+/// # use reportify::{Exit, Report};
+/// # reportify::new_whatever_type!(AppError);
+/// fn run() -> Result<(), Report<AppError>> {
+///     Ok(())
+/// }
+///
+/// fn main() -> Exit<AppError> {
+///     run().into()
+/// }
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct Exit<E>(Result<(), Report<E>>);
+
+impl<E> From<Result<(), Report<E>>> for Exit<E> {
+    fn from(result: Result<(), Report<E>>) -> Self {
+        Self(result)
+    }
+}
+
+impl<E> From<Report<E>> for Exit<E> {
+    fn from(report: Report<E>) -> Self {
+        Self(Err(report))
+    }
+}
+
+impl<E: Error> Termination for Exit<E> {
+    fn report(self) -> ExitCode {
+        match self.0 {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(report) => {
+                let format = render::Text::new().with_colors(console::colors_enabled_stderr());
+                if let Ok(rendered) = report.render_with(format) {
+                    eprint!("{rendered}");
+                }
+                ExitCode::from(match report.severity() {
+                    Severity::Warning => Severity::Warning.exit_code(),
+                    severity => report.error().exit_code().unwrap_or(severity.exit_code()),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::{ExitCode, Termination};
+
+    use super::Exit;
+    use crate::{Report, ResultExt};
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(
+            Exit::<std::io::Error>::from(Ok(())).report(),
+            ExitCode::SUCCESS
+        );
+        let report: Report<std::io::Error> = Err::<(), _>(std::io::Error::other("disk full"))
+            .context("writing cache")
+            .expect_err("should fail");
+        assert_eq!(Exit::from(report.downgrade()).report(), ExitCode::SUCCESS);
+    }
+}
@@ -0,0 +1,288 @@
+//! Structured fields recording the values of variables.
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display};
+use core::panic::Location;
+
+use crate::{Context, Report, ReportItem};
+
+/// Named values attached to a report as a single context item.
+///
+/// Fields are usually created with the [`fields!`][crate::fields!] macro. In text
+/// output, they are rendered as `name=value` pairs separated by spaces. Serialized
+/// reports preserve them as structured fields.
+///
+/// Owned values are stored as they are and only formatted when the report is rendered.
+/// Reports must not borrow from their surroundings, hence, borrowed values are
+/// formatted when the fields are created. As [`fields!`][crate::fields!] creates a
+/// closure, this only happens on the error path. The macro stores values only if they
+/// are explicitly moved with `move`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct Fields(Vec<Field>);
+
+impl Fields {
+    /// Create an empty set of fields.
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Add a field recording the [`Display`] representation of the borrowed value.
+    pub fn field(mut self, name: impl Into<Cow<'static, str>>, value: impl Display) -> Self {
+        self.0.push(Field::new(name, value.to_string()));
+        self
+    }
+
+    /// Add a field recording the [`Debug`] representation of the borrowed value.
+    pub fn debug_field(mut self, name: impl Into<Cow<'static, str>>, value: impl Debug) -> Self {
+        self.0.push(Field::new(name, alloc::format!("{value:?}")));
+        self
+    }
+
+    /// Add a field storing the owned value, recording its [`Display`] representation
+    /// when rendered.
+    pub fn value(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl Display + Send + Sync + 'static,
+    ) -> Self {
+        self.0.push(Field {
+            name: name.into(),
+            value: FieldValue::Display(Arc::new(value)),
+        });
+        self
+    }
+
+    /// Add a field storing the owned value, recording its [`Debug`] representation
+    /// when rendered.
+    pub fn debug_value(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl Debug + Send + Sync + 'static,
+    ) -> Self {
+        self.0.push(Field {
+            name: name.into(),
+            value: FieldValue::Debug(Arc::new(value)),
+        });
+        self
+    }
+
+    /// Iterator over the fields in the order they have been added.
+    pub fn iter(&self) -> impl Iterator<Item = &Field> {
+        self.0.iter()
+    }
+
+    /// Formatted value of the field with the given name, if any.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.0
+            .iter()
+            .find(|field| field.name == name)
+            .map(Field::value)
+    }
+}
+
+impl FromIterator<Field> for Fields {
+    fn from_iter<I: IntoIterator<Item = Field>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Display for Fields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, field) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{field}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<E> Context<E> for Fields {
+    #[track_caller]
+    fn attach_to(self, report: &mut Report<E>) {
+        report
+            .context
+            .push(Location::caller(), ReportItem::Fields(self));
+    }
+}
+
+/// Named value, formatted or formatted when rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "FieldData", from = "FieldData"))]
+pub struct Field {
+    name: Cow<'static, str>,
+    value: FieldValue,
+}
+
+/// Value of a field.
+#[derive(Clone)]
+enum FieldValue {
+    /// Formatted representation of a borrowed value.
+    Formatted(String),
+    /// Owned value recorded with its [`Display`] representation.
+    Display(Arc<dyn Display + Send + Sync>),
+    /// Owned value recorded with its [`Debug`] representation.
+    Debug(Arc<dyn Debug + Send + Sync>),
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Formatted(value) => f.write_str(value),
+            FieldValue::Display(value) => value.fmt(f),
+            FieldValue::Debug(value) => value.fmt(f),
+        }
+    }
+}
+
+impl Debug for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.to_string(), f)
+    }
+}
+
+impl PartialEq for FieldValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (FieldValue::Formatted(this), FieldValue::Formatted(other)) => this == other,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl Eq for FieldValue {}
+
+/// Serialized representation of a field with its formatted value.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FieldData {
+    name: Cow<'static, str>,
+    value: String,
+}
+
+#[cfg(feature = "serde")]
+impl From<Field> for FieldData {
+    fn from(field: Field) -> Self {
+        let value = field.value().into_owned();
+        Self {
+            name: field.name,
+            value,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<FieldData> for Field {
+    fn from(data: FieldData) -> Self {
+        Field::new(data.name, data.value)
+    }
+}
+
+impl Field {
+    /// Create a field with the given name and formatted value.
+    pub fn new(name: impl Into<Cow<'static, str>>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: FieldValue::Formatted(value.into()),
+        }
+    }
+
+    /// Name of the field.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Formatted value of the field.
+    #[must_use]
+    pub fn value(&self) -> Cow<'_, str> {
+        match &self.value {
+            FieldValue::Formatted(value) => Cow::Borrowed(value),
+            value => Cow::Owned(value.to_string()),
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{Report, ResultExt, Whatever};
+
+    crate::new_whatever_type!(LoadError);
+
+    #[derive(Debug)]
+    struct Config {
+        strict: bool,
+    }
+
+    fn load(path: &str, retries: u32, config: &Config) -> Result<(), Report<LoadError>> {
+        Err(LoadError::new()).context(crate::fields!(
+            path,
+            retries,
+            ?config,
+            strict = config.strict
+        ))
+    }
+
+    #[test]
+    fn test_fields() {
+        let report = load("config.toml", 3, &Config { strict: true }).expect_err("should fail");
+        let rendered = report.to_string();
+        assert!(
+            rendered.contains(
+                ": path=config.toml retries=3 config=Config { strict: true } strict=true\n"
+            ),
+            "{rendered}"
+        );
+        let fields = crate::fields!(name = %"value", debug = ?"value")();
+        assert_eq!(fields.get("name").as_deref(), Some("value"));
+        assert_eq!(fields.get("debug").as_deref(), Some("\"value\""));
+    }
+
+    #[test]
+    fn test_borrowed_fields() {
+        let path = std::path::PathBuf::from("config.toml");
+        let config = Config { strict: false };
+        let fields = crate::fields!(path = %path.display(), config = ?&config)();
+        assert_eq!(fields.get("path").as_deref(), Some("config.toml"));
+        assert_eq!(
+            fields.get("config").as_deref(),
+            Some("Config { strict: false }")
+        );
+    }
+
+    #[test]
+    fn test_owned_fields() {
+        #[derive(Debug)]
+        struct Counter(Arc<AtomicUsize>);
+
+        impl std::fmt::Display for Counter {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0.load(Ordering::Relaxed))
+            }
+        }
+
+        let count = Arc::new(AtomicUsize::new(1));
+        let report = Err::<(), _>(LoadError::new())
+            .context(crate::fields!(count = move Counter(count.clone())))
+            .expect_err("should fail");
+        count.store(2, Ordering::Relaxed);
+        let rendered = report.to_string();
+        assert!(rendered.contains(": count=2\n"), "{rendered}");
+    }
+}
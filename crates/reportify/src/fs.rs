@@ -0,0 +1,271 @@
+//! Filesystem operations reporting the failed operation and path.
+//!
+//! The errors returned by [`std::fs`] do not include the operation that failed or the
+//! path involved. The functions of this module mirror their [`std::fs`] counterparts but
+//! return reports with the operation and path attached as context. For common error
+//! kinds, e.g., [`NotFound`][io::ErrorKind::NotFound] and
+//! [`PermissionDenied`][io::ErrorKind::PermissionDenied], reports also include a
+//! suggestion on how to fix the cause of the error.
+
+#![expect(
+    clippy::missing_errors_doc,
+    reason = "the functions fail like their `std::fs` counterparts"
+)]
+
+use std::fmt::{self, Display};
+use std::fs::{File, Metadata, ReadDir};
+use std::io;
+use std::path::Path;
+
+use crate::{Help, Report, ResultExt};
+
+/// Read the entire contents of a file into a bytes vector.
+///
+/// See [`std::fs::read`].
+#[track_caller]
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<u8>, Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(std::fs::read(path), Operation::Read(path))
+}
+
+/// Read the entire contents of a file into a string.
+///
+/// See [`std::fs::read_to_string`].
+#[track_caller]
+pub fn read_to_string(path: impl AsRef<Path>) -> Result<String, Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(std::fs::read_to_string(path), Operation::Read(path))
+}
+
+/// Write a slice as the entire contents of a file.
+///
+/// See [`std::fs::write`].
+#[track_caller]
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<(), Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(std::fs::write(path, contents), Operation::Write(path))
+}
+
+/// Open a file in read-only mode.
+///
+/// See [`File::open`].
+#[track_caller]
+pub fn open(path: impl AsRef<Path>) -> Result<File, Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(File::open(path), Operation::Open(path))
+}
+
+/// Open a file in write-only mode, creating or truncating it.
+///
+/// See [`File::create`].
+#[track_caller]
+pub fn create(path: impl AsRef<Path>) -> Result<File, Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(File::create(path), Operation::Create(path))
+}
+
+/// Create a new file in read-write mode, failing if the file already exists.
+///
+/// See [`File::create_new`].
+#[track_caller]
+pub fn create_new(path: impl AsRef<Path>) -> Result<File, Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(File::create_new(path), Operation::Create(path))
+}
+
+/// Recursively create a directory and all of its parent components if they are missing.
+///
+/// See [`std::fs::create_dir_all`].
+#[track_caller]
+pub fn create_dir_all(path: impl AsRef<Path>) -> Result<(), Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(std::fs::create_dir_all(path), Operation::CreateDirAll(path))
+}
+
+/// Rename a file or directory, replacing the destination if it already exists.
+///
+/// See [`std::fs::rename`].
+#[track_caller]
+pub fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<(), Report<io::Error>> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    with_context(std::fs::rename(from, to), Operation::Rename(from, to))
+}
+
+/// Remove a file.
+///
+/// See [`std::fs::remove_file`].
+#[track_caller]
+pub fn remove_file(path: impl AsRef<Path>) -> Result<(), Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(std::fs::remove_file(path), Operation::RemoveFile(path))
+}
+
+/// Query the metadata of a file or directory.
+///
+/// See [`std::fs::metadata`].
+#[track_caller]
+pub fn metadata(path: impl AsRef<Path>) -> Result<Metadata, Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(std::fs::metadata(path), Operation::Metadata(path))
+}
+
+/// Return an iterator over the entries of a directory.
+///
+/// See [`std::fs::read_dir`].
+#[track_caller]
+pub fn read_dir(path: impl AsRef<Path>) -> Result<ReadDir, Report<io::Error>> {
+    let path = path.as_ref();
+    with_context(std::fs::read_dir(path), Operation::ReadDir(path))
+}
+
+/// Copy the contents of a file to another file.
+///
+/// See [`std::fs::copy`].
+#[track_caller]
+pub fn copy(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<u64, Report<io::Error>> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    with_context(std::fs::copy(from, to), Operation::Copy(from, to))
+}
+
+/// Attach the operation and a suggestion, if any, to the result.
+#[track_caller]
+fn with_context<T>(
+    result: io::Result<T>,
+    operation: Operation<'_>,
+) -> Result<T, Report<io::Error>> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(error) => {
+            let help = operation.help(error.kind());
+            let result = Err(error).context(operation.to_string());
+            match help {
+                Some(help) => result.context(Help::new(help)),
+                None => result,
+            }
+        }
+    }
+}
+
+/// Filesystem operation.
+#[derive(Debug, Clone, Copy)]
+enum Operation<'p> {
+    Read(&'p Path),
+    Write(&'p Path),
+    Open(&'p Path),
+    Create(&'p Path),
+    CreateDirAll(&'p Path),
+    Rename(&'p Path, &'p Path),
+    RemoveFile(&'p Path),
+    Metadata(&'p Path),
+    ReadDir(&'p Path),
+    Copy(&'p Path, &'p Path),
+}
+
+impl Operation<'_> {
+    /// Path the operation applies to or, for operations with two paths, the source path.
+    fn path(&self) -> &Path {
+        match self {
+            Operation::Read(path)
+            | Operation::Write(path)
+            | Operation::Open(path)
+            | Operation::Create(path)
+            | Operation::CreateDirAll(path)
+            | Operation::Rename(path, _)
+            | Operation::RemoveFile(path)
+            | Operation::Metadata(path)
+            | Operation::ReadDir(path)
+            | Operation::Copy(path, _) => path,
+        }
+    }
+
+    /// Suggestion on how to fix the cause of an error of the given kind.
+    fn help(&self, kind: io::ErrorKind) -> Option<String> {
+        let path = self.path().display();
+        Some(match (kind, self) {
+            // Missing ancestors are created, so there is nothing to check for.
+            (io::ErrorKind::NotFound, Operation::CreateDirAll(_)) => return None,
+            (io::ErrorKind::NotFound, Operation::Write(path) | Operation::Create(path)) => format!(
+                "check that the parent directory of `{}` exists",
+                path.display()
+            ),
+            (io::ErrorKind::NotFound, Operation::Rename(from, to) | Operation::Copy(from, to)) => {
+                format!(
+                    "check that `{}` exists and that the parent directory of `{}` exists",
+                    from.display(),
+                    to.display()
+                )
+            }
+            (io::ErrorKind::NotFound, _) => format!("check that `{path}` exists"),
+            (
+                io::ErrorKind::PermissionDenied,
+                Operation::Rename(from, to) | Operation::Copy(from, to),
+            ) => {
+                format!(
+                    "check that you have the necessary permissions to access `{}` and `{}`",
+                    from.display(),
+                    to.display()
+                )
+            }
+            (io::ErrorKind::PermissionDenied, _) => {
+                format!("check that you have the necessary permissions to access `{path}`")
+            }
+            (io::ErrorKind::AlreadyExists, _) => {
+                format!("remove `{path}` or choose a different path")
+            }
+            (io::ErrorKind::IsADirectory, _) => {
+                format!("`{path}` is a directory, provide the path of a file instead")
+            }
+            (io::ErrorKind::NotADirectory, _) => {
+                format!("`{path}` or one of its ancestors is not a directory")
+            }
+            (io::ErrorKind::StorageFull, _) => "free up space on the storage device".to_owned(),
+            (io::ErrorKind::ReadOnlyFilesystem, _) => {
+                format!("`{path}` is located on a read-only filesystem")
+            }
+            _ => return None,
+        })
+    }
+}
+
+impl Display for Operation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Read(path) => write!(f, "reading file `{}`", path.display()),
+            Operation::Write(path) => write!(f, "writing file `{}`", path.display()),
+            Operation::Open(path) => write!(f, "opening file `{}`", path.display()),
+            Operation::Create(path) => write!(f, "creating file `{}`", path.display()),
+            Operation::CreateDirAll(path) => {
+                write!(f, "creating directory `{}`", path.display())
+            }
+            Operation::Rename(from, to) => {
+                write!(f, "renaming `{}` to `{}`", from.display(), to.display())
+            }
+            Operation::RemoveFile(path) => write!(f, "removing file `{}`", path.display()),
+            Operation::Metadata(path) => {
+                write!(f, "querying metadata of `{}`", path.display())
+            }
+            Operation::ReadDir(path) => write!(f, "reading directory `{}`", path.display()),
+            Operation::Copy(from, to) => {
+                write!(f, "copying `{}` to `{}`", from.display(), to.display())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_read_not_found() {
+        let report = super::read_to_string("does/not/exist.txt").expect_err("should fail");
+        let rendered = report.to_string();
+        assert!(rendered.contains("src/fs.rs:"));
+        assert!(rendered.contains(": reading file `does/not/exist.txt`"));
+        assert!(rendered.contains("help: check that `does/not/exist.txt` exists"));
+    }
+
+    #[test]
+    fn test_create_dir_all_not_found() {
+        let operation = super::Operation::CreateDirAll(std::path::Path::new("does/not/exist"));
+        assert_eq!(operation.help(std::io::ErrorKind::NotFound), None);
+    }
+}
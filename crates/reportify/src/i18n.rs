@@ -0,0 +1,263 @@
+//! Localization of user-facing messages.
+//!
+//! Errors and context items can carry a [`Message`] consisting of a key, named
+//! arguments, and an English fallback. When a report is rendered, messages are resolved
+//! through the globally installed [`Catalog`]. If no catalog is installed
+//! or the catalog has no translation for a key, the fallback is used.
+//!
+//! ```ignore
+//! reportify::i18n::install(
+//!     Translations::new()
+//!         .with("config-not-found", "Konfigurationsdatei { $path } nicht gefunden"),
+//! );
+//!
+//! let result = read_config(path).context(
+//!     Message::new("config-not-found", format!("config file {path} not found"))
+//!         .arg("path", path),
+//! );
+//! ```
+//!
+//! The messages of errors are localized by implementing [`Error::localized_message`].
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::panic::Location;
+#[cfg(feature = "std")]
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::{Context, Error, Report, ReportItem};
+
+#[cfg(feature = "std")]
+static CATALOG: RwLock<Option<Arc<dyn Catalog>>> = RwLock::new(None);
+
+/// Install the given catalog globally.
+#[cfg(feature = "std")]
+pub fn install(catalog: impl Catalog) {
+    *CATALOG.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(catalog));
+}
+
+/// Remove the globally installed catalog, if any.
+#[cfg(feature = "std")]
+pub fn uninstall() {
+    *CATALOG.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Source of translations for localized messages.
+pub trait Catalog: Send + Sync + 'static {
+    /// Translate the given message, returning [`None`] if there is no translation.
+    fn translate(&self, message: &Message) -> Option<String>;
+}
+
+/// Localizable message with named arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    key: Cow<'static, str>,
+    args: Vec<(Cow<'static, str>, String)>,
+    fallback: String,
+}
+
+impl Message {
+    /// Create a message with the given key and English fallback.
+    pub fn new(key: impl Into<Cow<'static, str>>, fallback: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+            fallback: fallback.into(),
+        }
+    }
+
+    /// Add a named argument.
+    #[must_use]
+    pub fn arg(mut self, name: impl Into<Cow<'static, str>>, value: impl Display) -> Self {
+        self.args.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// Key of the message.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Value of the argument with the given name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .find(|(arg, _)| arg == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterator over the named arguments.
+    pub fn args(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.args
+            .iter()
+            .map(|(name, value)| (name.as_ref(), value.as_str()))
+    }
+
+    /// English fallback of the message.
+    #[must_use]
+    pub fn fallback(&self) -> &str {
+        &self.fallback
+    }
+
+    /// Translate the message with the installed catalog, if any.
+    #[cfg(feature = "std")]
+    fn translate(&self) -> Option<String> {
+        CATALOG
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()?
+            .translate(self)
+    }
+
+    /// Without the `std` feature, catalogs cannot be installed.
+    #[cfg(not(feature = "std"))]
+    #[expect(
+        clippy::unused_self,
+        reason = "mirrors the signature with the `std` feature"
+    )]
+    fn translate(&self) -> Option<String> {
+        None
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.translate() {
+            Some(translated) => f.write_str(&translated),
+            None => f.write_str(&self.fallback),
+        }
+    }
+}
+
+impl<E> Context<E> for Message {
+    #[track_caller]
+    fn attach_to(self, report: &mut Report<E>) {
+        report
+            .context
+            .push(Location::caller(), ReportItem::Localized(Box::new(self)));
+    }
+}
+
+/// Catalog of translations given as patterns.
+///
+/// Patterns reference arguments with Fluent-style placeables, e.g., `{ $path }`.
+/// Placeables referencing missing arguments are kept verbatim.
+#[derive(Debug, Clone, Default)]
+pub struct Translations {
+    patterns: BTreeMap<Cow<'static, str>, Cow<'static, str>>,
+}
+
+impl Translations {
+    /// Create an empty catalog.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the pattern for the given key.
+    #[must_use]
+    pub fn with(
+        mut self,
+        key: impl Into<Cow<'static, str>>,
+        pattern: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.insert(key, pattern);
+        self
+    }
+
+    /// Add the pattern for the given key.
+    pub fn insert(
+        &mut self,
+        key: impl Into<Cow<'static, str>>,
+        pattern: impl Into<Cow<'static, str>>,
+    ) {
+        self.patterns.insert(key.into(), pattern.into());
+    }
+}
+
+impl Catalog for Translations {
+    fn translate(&self, message: &Message) -> Option<String> {
+        let mut pattern = self.patterns.get(message.key())?.as_ref();
+        let mut output = String::with_capacity(pattern.len());
+        while let Some(start) = pattern.find('{') {
+            output.push_str(&pattern[..start]);
+            let rest = &pattern[start..];
+            let Some(end) = rest.find('}') else {
+                pattern = rest;
+                break;
+            };
+            let placeable = &rest[..=end];
+            let value = placeable[1..end]
+                .trim()
+                .strip_prefix('$')
+                .and_then(|name| message.get(name));
+            output.push_str(value.unwrap_or(placeable));
+            pattern = &rest[end + 1..];
+        }
+        output.push_str(pattern);
+        Some(output)
+    }
+}
+
+/// Message of an error, translated if possible.
+pub(crate) enum ErrorMessage<'e> {
+    Message(&'e dyn Display),
+    Translated(String),
+}
+
+impl ErrorMessage<'_> {
+    /// Resolve the message of the given error.
+    pub(crate) fn of<E: Error + ?Sized>(error: &E) -> Option<ErrorMessage<'_>> {
+        if let Some(message) = error.localized_message()
+            && let Some(translated) = message.translate()
+        {
+            return Some(ErrorMessage::Translated(translated));
+        }
+        error.message().map(ErrorMessage::Message)
+    }
+}
+
+impl Display for ErrorMessage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorMessage::Message(message) => message.fmt(f),
+            ErrorMessage::Translated(translated) => f.write_str(translated),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Catalog, Message, Translations};
+
+    #[test]
+    fn test_translations() {
+        let translations = Translations::new()
+            .with(
+                "not-found",
+                "{ $path } nicht gefunden ({$code}, { $missing })",
+            )
+            .with("unbalanced", "Klammer {");
+        let message = Message::new("not-found", "not found")
+            .arg("path", "a.toml")
+            .arg("code", 2);
+        assert_eq!(
+            translations.translate(&message).as_deref(),
+            Some("a.toml nicht gefunden (2, { $missing })")
+        );
+        let message = Message::new("unbalanced", "bracket");
+        assert_eq!(
+            translations.translate(&message).as_deref(),
+            Some("Klammer {")
+        );
+        let message = Message::new("unknown", "unknown");
+        assert_eq!(translations.translate(&message), None);
+        assert_eq!(message.to_string(), "unknown");
+    }
+}
@@ -0,0 +1,189 @@
+//! Inspection of the errors a report consists of.
+
+use alloc::boxed::Box;
+use core::any::Any;
+use core::error::Error as StdError;
+use core::fmt::{self, Debug, Display};
+use core::iter;
+
+use crate::i18n::ErrorMessage;
+use crate::{Error, Report};
+
+/// Error that can be downcast to its concrete type.
+pub(crate) trait AnyError: Error {
+    /// Cast the error into [`Any`].
+    fn as_any(&self) -> &dyn Any;
+
+    /// Name of the error type.
+    fn type_name(&self) -> &'static str;
+}
+
+impl<E: Error> AnyError for E {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn type_name(&self) -> &'static str {
+        core::any::type_name::<E>()
+    }
+}
+
+impl Debug for dyn AnyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.type_name())
+    }
+}
+
+impl<E: Error> Report<E> {
+    /// Iterator over the error layers of the report.
+    ///
+    /// The layers start with the current error and its [sources][StdError::source],
+    /// followed by the errors the report has previously been propagated from, most
+    /// recent first, each followed by its sources. Previous errors are retained when
+    /// propagating a report via [`Whatever`][crate::Whatever] or
+    /// [`Report::whatever_as`]; errors converted into other errors are only available
+    /// if they are sources of the converted errors.
+    pub fn layers(&self) -> impl Iterator<Item = Layer<'_>> {
+        iter::once(&self.error as &dyn AnyError)
+            .chain(self.context.prior.iter().rev().map(Box::as_ref))
+            .flat_map(|error| {
+                let sources =
+                    iter::successors(error.as_std_error().and_then(StdError::source), |&source| {
+                        source.source()
+                    });
+                iter::once(Layer(LayerKind::Error(error)))
+                    .chain(sources.map(|source| Layer(LayerKind::Source(source))))
+            })
+    }
+
+    /// First error of the given type in the [layers][Report::layers] of the report.
+    #[must_use]
+    pub fn find<T: Error>(&self) -> Option<&T> {
+        self.layers().find_map(Layer::downcast_ref)
+    }
+
+    /// Check whether the [layers][Report::layers] of the report contain an error of the
+    /// given type.
+    #[must_use]
+    pub fn is<T: Error>(&self) -> bool {
+        self.find::<T>().is_some()
+    }
+}
+
+/// Error layer of a report.
+///
+/// See [`Report::layers`].
+#[derive(Clone, Copy)]
+pub struct Layer<'r>(LayerKind<'r>);
+
+#[derive(Clone, Copy)]
+enum LayerKind<'r> {
+    /// Current or previous error of the report.
+    Error(&'r dyn AnyError),
+    /// Source of an error.
+    Source(&'r (dyn StdError + 'static)),
+}
+
+impl<'r> Layer<'r> {
+    /// Downcast the error to the given type.
+    #[must_use]
+    pub fn downcast_ref<T: Error>(self) -> Option<&'r T> {
+        match self.0 {
+            LayerKind::Error(error) => error.as_any().downcast_ref(),
+            LayerKind::Source(source) => T::downcast_source(source),
+        }
+    }
+
+    /// Check whether the error is of the given type.
+    #[must_use]
+    pub fn is<T: Error>(self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+}
+
+impl Display for Layer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            LayerKind::Error(error) => match ErrorMessage::of(error) {
+                Some(message) => Display::fmt(&message, f),
+                None => f.write_str("<unknown error>"),
+            },
+            LayerKind::Source(source) => Display::fmt(source, f),
+        }
+    }
+}
+
+impl Debug for Layer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            LayerKind::Error(_) => f
+                .debug_tuple("Error")
+                .field(&format_args!("{self}"))
+                .finish(),
+            LayerKind::Source(source) => f.debug_tuple("Source").field(source).finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use crate::{Report, ResultExt};
+
+    crate::new_whatever_type!(LoadError);
+    crate::new_whatever_type!(AppError);
+
+    #[derive(Debug)]
+    struct Timeout;
+
+    impl fmt::Display for Timeout {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("operation timed out")
+        }
+    }
+
+    impl std::error::Error for Timeout {}
+
+    #[derive(Debug)]
+    struct FetchError(Timeout);
+
+    impl fmt::Display for FetchError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("unable to fetch data")
+        }
+    }
+
+    impl std::error::Error for FetchError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    fn load() -> Result<(), Report<LoadError>> {
+        Err::<(), _>(FetchError(Timeout)).whatever()
+    }
+
+    #[test]
+    fn test_layers() {
+        let report = load().whatever::<AppError>().expect_err("should fail");
+        assert!(report.is::<AppError>());
+        assert!(report.is::<LoadError>());
+        assert!(report.is::<FetchError>());
+        assert!(report.is::<Timeout>());
+        assert!(!report.is::<fmt::Error>());
+        let layers = report
+            .layers()
+            .map(|layer| layer.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            layers,
+            [
+                "<unknown error>",
+                "<unknown error>",
+                "unable to fetch data",
+                "operation timed out"
+            ]
+        );
+    }
+}
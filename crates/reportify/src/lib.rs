@@ -1,10 +1,75 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::error::Error as StdError;
+use core::fmt::{self, Display};
+use core::panic::Location;
+#[cfg(feature = "std")]
 use std::backtrace::{Backtrace, BacktraceStatus};
-use std::error::Error as StdError;
-use std::fmt::Display;
-use std::panic::Location;
 
+#[cfg(feature = "std")]
 use tracing_error::{SpanTrace, SpanTraceStatus};
 
+#[cfg(feature = "std")]
+pub mod backtrace;
+#[cfg(feature = "std")]
+mod bug_report;
+#[cfg(feature = "clap4")]
+pub mod clap;
+mod collect;
+#[cfg(feature = "deserialize")]
+pub mod deserialize;
+mod ensure;
+#[cfg(feature = "std")]
+mod exit;
+mod fields;
+#[cfg(feature = "std")]
+pub mod fs;
+pub mod i18n;
+mod layers;
+mod light;
+mod limits;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "opentelemetry")]
+pub mod otel;
+#[cfg(feature = "std")]
+pub mod process;
+#[cfg(feature = "serde")]
+pub mod remote;
+pub mod render;
+mod severity;
+mod timeline;
+
+#[cfg(feature = "std")]
+pub use bug_report::BugReport;
+pub use collect::{Failures, IteratorExt, Outcome};
+#[cfg(feature = "std")]
+pub use exit::Exit;
+pub use fields::{Field, Fields};
+pub use layers::Layer;
+pub use light::LightReport;
+pub use limits::Limits;
+#[cfg(feature = "std")]
+pub use metrics::stats;
+pub use reportify_macros::context;
+pub use severity::Severity;
+#[cfg(feature = "std")]
+pub use timeline::Timeline;
+
+#[cfg(feature = "std")]
+use crate::backtrace::BacktraceFilter;
+use crate::ensure::Assertion;
+use crate::i18n::ErrorMessage;
+use crate::limits::TruncatingFormat;
+use crate::render::{Format, NestedReport};
+
 /// Error with additional context information for reporting.
 #[derive(Debug)]
 pub struct Report<E> {
@@ -14,13 +79,24 @@ pub struct Report<E> {
 
 impl<E: Error> Report<E> {
     /// Create a new report from the given error and context.
-    pub fn new(error: E, context: ReportContext) -> Self {
+    pub fn new(error: E, mut context: ReportContext) -> Self {
+        #[cfg(feature = "std")]
+        metrics::record(&error, metrics::Event::Created);
+        context.mark_error_layer::<E>();
         Self {
             error,
             context: Box::new(context),
         }
     }
 
+    /// Create a lightweight report from the given error.
+    ///
+    /// See [`LightReport`] for details.
+    #[track_caller]
+    pub fn light(error: E) -> LightReport<E> {
+        LightReport::new(error)
+    }
+
     /// Underlying error.
     pub fn error(&self) -> &E {
         &self.error
@@ -31,20 +107,104 @@ impl<E: Error> Report<E> {
         &self.context
     }
 
+    /// Severity of the report.
+    ///
+    /// Defaults to the [severity of the error][Error::severity].
+    pub fn severity(&self) -> Severity {
+        self.context
+            .severity
+            .unwrap_or_else(|| self.error.severity())
+    }
+
+    /// Set the severity of the report.
+    #[must_use]
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.context.severity = Some(severity);
+        self
+    }
+
+    /// Lower the severity of the report by one level, e.g., from error to warning.
+    #[must_use]
+    pub fn downgrade(self) -> Self {
+        let severity = self.severity().downgrade();
+        self.with_severity(severity)
+    }
+
+    /// Raise the severity of the report by one level, e.g., from error to fatal.
+    #[must_use]
+    pub fn escalate(self) -> Self {
+        let severity = self.severity().escalate();
+        self.with_severity(severity)
+    }
+
+    /// Log the report as ignored at the tracing level of its severity.
+    fn log_ignored(&self) {
+        #[cfg(feature = "std")]
+        metrics::record(&self.error, metrics::Event::Logged);
+        #[cfg(feature = "opentelemetry")]
+        self.record_exception();
+        // Tracing requires the level of an event to be known statically.
+        if self.severity().level() == tracing::Level::WARN {
+            tracing::warn!("ignoring error\n\n{self}");
+        } else {
+            tracing::error!("ignoring error\n\n{self}");
+        }
+    }
+
     /// Consume the report and return the underlying error and context.
     pub fn into_parts(self) -> (E, ReportContext) {
         (self.error, *self.context)
     }
 
+    #[must_use]
+    #[track_caller]
     pub fn with_context<C: Context<E>>(mut self, context: C) -> Self {
         context.attach_to(&mut self);
         self
     }
 
+    /// Attach the given context as if it has been attached at the given location and
+    /// with the given information.
+    fn attach_deferred<C: Context<E>>(
+        &mut self,
+        location: &'static Location<'static>,
+        info: Option<Box<timeline::ItemInfo>>,
+        context: C,
+    ) {
+        self.context.deferred = Some((location, info));
+        context.attach_to(self);
+        self.context.deferred = None;
+    }
+
+    /// Propagate the report using [`Whatever`] to construct the new error.
+    #[track_caller]
     pub fn whatever<F: Whatever>(self) -> Report<F> {
         F::propagate(self)
     }
 
+    /// Propagate the report as the given error.
+    ///
+    /// This is useful for errors which cannot be constructed via [`Whatever`], e.g.,
+    /// variants of an error enum created with [`new_error_enum!`].
+    #[track_caller]
+    pub fn whatever_as<F: Error>(self, error: F) -> Report<F> {
+        self.propagate_retaining(error)
+    }
+
+    /// Propagate the report as the given error, retaining the current error.
+    ///
+    /// The retained errors are available via [`Report::layers`].
+    #[track_caller]
+    fn propagate_retaining<F: Error>(self, error: F) -> Report<F> {
+        let mut previous = None;
+        let mut report = self.propagate_map(|current| {
+            previous = Some(Box::new(current) as Box<dyn layers::AnyError>);
+            error
+        });
+        report.context.prior.extend(previous);
+        report
+    }
+
     /// Propagate the report converting the error using the given function.
     #[track_caller]
     fn propagate_map<F, M>(self, map: M) -> Report<F>
@@ -52,15 +212,17 @@ impl<E: Error> Report<E> {
         M: FnOnce(E) -> F,
     {
         let mut context = self.context;
-        let error_item = context.items.get_mut(context.error_item).unwrap();
         // Materialize or discard the error message before mapping the error.
-        if let Some(message) = self.error.message() {
-            error_item.1 = ReportItem::Message(message.to_string());
-        } else {
-            error_item.1 = ReportItem::Discarded;
-        }
+        context.materialize_error_item(match self.error.localized_message() {
+            Some(message) => ReportItem::Localized(Box::new(message)),
+            None => match self.error.message() {
+                Some(message) => ReportItem::Message(message.to_string()),
+                None => ReportItem::Discarded,
+            },
+        });
         context.error_item = context.items.len();
-        context.items.push((Location::caller(), ReportItem::Error));
+        context.push(Location::caller(), ReportItem::Error);
+        context.mark_error_layer::<F>();
         Report {
             error: map(self.error),
             context,
@@ -78,51 +240,324 @@ impl<E: Error, F: Error + Into<E>> From<F> for Report<E> {
     }
 }
 
-impl<E: Error> std::fmt::Display for Report<E> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<E: Error> Report<E> {
+    /// Render the report as Markdown.
+    #[must_use]
+    #[expect(
+        clippy::missing_panics_doc,
+        reason = "built-in formats do not fail when writing to a string"
+    )]
+    pub fn render_markdown(&self) -> String {
+        self.render_with(render::Markdown::new())
+            .expect("Markdown format should not fail")
+    }
+
+    /// Render the report as HTML.
+    #[must_use]
+    #[expect(
+        clippy::missing_panics_doc,
+        reason = "built-in formats do not fail when writing to a string"
+    )]
+    pub fn render_html(&self) -> String {
+        self.render_with(render::Html)
+            .expect("HTML format should not fail")
+    }
+
+    /// Render the report in the given format.
+    ///
+    /// Like the plain text rendering, the output is truncated according to the
+    /// configured [`Limits`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the format returns an error.
+    pub fn render_with<F: Format>(&self, mut format: F) -> Result<String, fmt::Error> {
+        self.render_to_string(&mut format, false)
+    }
+
+    /// Render the report as plain text with the context items grouped by error layer.
+    ///
+    /// See [`Report::render_layered_with`].
+    #[must_use]
+    #[expect(
+        clippy::missing_panics_doc,
+        reason = "built-in formats do not fail when writing to a string"
+    )]
+    pub fn render_layered(&self) -> String {
+        self.render_layered_with(render::Text::new())
+            .expect("text format should not fail")
+    }
+
+    /// Render the report in the given format with the context items grouped by error
+    /// layer.
+    ///
+    /// Each time a report is propagated as another error, a new error layer starts. The
+    /// layers are rendered most recent first, each starting with the name of its error
+    /// type followed by the context items attached to the error:
+    ///
+    /// ```text
+    /// unable to start server
+    ///
+    /// app::ServerError:
+    ///   src/main.rs:12:10: unable to start server
+    /// app::ConfigError:
+    ///   src/config.rs:31:5: loading `server.toml`
+    ///   src/config.rs:24:9: invalid port
+    /// ```
+    ///
+    /// Like the plain text rendering, the output is truncated according to the
+    /// configured [`Limits`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the format returns an error.
+    pub fn render_layered_with<F: Format>(&self, mut format: F) -> Result<String, fmt::Error> {
+        self.render_to_string(&mut format, true)
+    }
+
+    /// Render the report to a string truncated according to the configured [`Limits`].
+    fn render_to_string(
+        &self,
+        format: &mut dyn Format,
+        layered: bool,
+    ) -> Result<String, fmt::Error> {
+        let mut output = String::new();
+        let mut format = TruncatingFormat::new(format, Limits::current().max_rendered_len());
+        self.render(&mut output, &mut format, layered)?;
+        Ok(output)
+    }
+
+    /// Render the report to the given writer, optionally grouping the context items by
+    /// error layer.
+    fn render(
+        &self,
+        f: &mut dyn fmt::Write,
+        format: &mut dyn Format,
+        layered: bool,
+    ) -> fmt::Result {
         // TODO: It might make sense to just leave the formatting of the error to the
         // `Error` trait itself, such that it can be easily customized.
-        if let Some(message) = self.error.message() {
-            writeln!(f, "{}", message)?;
+        format.begin(f)?;
+        if let Some(message) = ErrorMessage::of(&self.error) {
+            format.message(f, self.severity(), &message.to_string())?;
         }
         if let Some(error) = self.error.as_std_error() {
             let mut source = error.source();
             while let Some(error) = source {
-                writeln!(f, "  Caused by: {}", error)?;
+                format.cause(f, &error.to_string())?;
                 source = error.source();
             }
         }
         if !self.context.items.is_empty() {
-            writeln!(f, "")?;
-            for (location, item) in self.context.items.iter().rev() {
-                match item {
-                    ReportItem::Message(message) => writeln!(f, "{location}: {message}")?,
-                    ReportItem::Error => {
-                        if let Some(message) = self.error.message() {
-                            writeln!(f, "{location}: {message}")?;
-                        }
+            format.begin_context(f)?;
+            let items = &self.context.items;
+            let mut end = items.len();
+            if layered {
+                for (start, entry) in items.iter().enumerate().rev() {
+                    let Some(error_type) = &entry.error_type else {
+                        continue;
+                    };
+                    format.begin_layer(f, error_type)?;
+                    for entry in items[start..end].iter().rev() {
+                        self.render_item(f, format, entry)?;
                     }
-                    ReportItem::Discarded => { /* Nothing to do. */ }
+                    format.end_layer(f)?;
+                    end = start;
                 }
             }
+            // Without layers, or for items preceding all error items, render flat.
+            for entry in items[..end].iter().rev() {
+                self.render_item(f, format, entry)?;
+            }
+            format.end_context(f)?;
         }
+        let help = self
+            .context
+            .items
+            .iter()
+            .filter_map(|entry| match &entry.item {
+                ReportItem::Help(help) => Some(help.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !help.is_empty() {
+            format.help(f, &help)?;
+        }
+        for (title, report) in self.error.nested_reports() {
+            format.begin_nested(f, &title)?;
+            report.render_nested(f, format)?;
+            format.end_nested(f)?;
+        }
+        #[cfg(feature = "std")]
         if self.context.backtrace.status() == BacktraceStatus::Captured {
-            writeln!(f, "\nBacktrace:\n{}", self.context.backtrace)?;
+            let mut backtrace = String::new();
+            BacktraceFilter::current().render(&mut backtrace, &self.context.backtrace)?;
+            format.section(f, "Backtrace", &backtrace)?;
         }
+        #[cfg(feature = "std")]
         if self.context.span_trace.status() == SpanTraceStatus::CAPTURED {
-            writeln!(f, "\nSpan Trace:\n{}", self.context.span_trace)?;
+            format.section(f, "Span Trace", &self.context.span_trace.to_string())?;
         }
-        Ok(())
+        #[cfg(feature = "serde")]
+        for remote in &self.context.remote {
+            if let Some(backtrace) = &remote.backtrace {
+                let mut rendered = String::new();
+                BacktraceFilter::current().render_text(&mut rendered, backtrace)?;
+                format.section(f, "Remote Backtrace", &rendered)?;
+            }
+            if let Some(span_trace) = &remote.span_trace {
+                format.section(f, "Remote Span Trace", span_trace)?;
+            }
+        }
+        format.end(f)
+    }
+
+    /// Render a context entry.
+    fn render_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        format: &mut dyn Format,
+        entry: &ContextEntry,
+    ) -> fmt::Result {
+        let message = match &entry.item {
+            ReportItem::Message(message) => message.clone(),
+            ReportItem::Localized(message) => message.to_string(),
+            ReportItem::Fields(fields) => fields.to_string(),
+            ReportItem::Error => match ErrorMessage::of(&self.error) {
+                Some(message) => message.to_string(),
+                None => return Ok(()),
+            },
+            ReportItem::Assertion(assertion) => assertion.to_string(),
+            ReportItem::Omitted(count) => return format.omitted(f, *count),
+            ReportItem::Help(_) | ReportItem::Discarded => return Ok(()),
+        };
+        let location = match &entry.info {
+            Some(info) => format!("{} [{info}]", entry.location),
+            None => entry.location.to_string(),
+        };
+        format.context_item(f, &location, &message, entry.repeated)
+    }
+}
+
+impl<E: Error> NestedReport for Report<E> {
+    fn render_nested(&self, f: &mut dyn fmt::Write, format: &mut dyn Format) -> fmt::Result {
+        self.render(f, format, false)
+    }
+}
+
+impl<E: Error> Display for Report<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut text = render::Text::new();
+        let mut format = TruncatingFormat::new(&mut text, Limits::current().max_rendered_len());
+        self.render(f, &mut format, false)
     }
 }
 
 /// Context for error reporting.
 #[derive(Debug)]
 pub struct ReportContext {
+    #[cfg(feature = "std")]
     backtrace: Backtrace,
+    #[cfg(feature = "std")]
     span_trace: SpanTrace,
-    items: Vec<(&'static Location<'static>, ReportItem)>,
+    /// Traces of reports reconstructed from other processes.
+    #[cfg(feature = "serde")]
+    remote: Vec<remote::RemoteTraces>,
+    /// Errors the report has previously been propagated from.
+    prior: Vec<Box<dyn layers::AnyError>>,
+    /// Severity overriding the severity of the error.
+    severity: Option<Severity>,
+    items: Vec<ContextEntry>,
     error_item: usize,
+    /// Location and information of deferred context being attached, overriding the
+    /// ones of the items pushed while attaching it.
+    deferred: Option<(&'static Location<'static>, Option<Box<timeline::ItemInfo>>)>,
+}
+
+/// Location at which an item has been attached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ItemLocation {
+    /// Location in this process.
+    Local(&'static Location<'static>),
+    /// Location in another process, reconstructed from a serialized report.
+    #[cfg(feature = "serde")]
+    Remote {
+        file: Box<str>,
+        line: u32,
+        column: u32,
+    },
+}
+
+impl ItemLocation {
+    /// Source file of the location.
+    fn file(&self) -> &str {
+        match self {
+            ItemLocation::Local(location) => location.file(),
+            #[cfg(feature = "serde")]
+            ItemLocation::Remote { file, .. } => file,
+        }
+    }
+
+    /// Source line of the location.
+    fn line(&self) -> u32 {
+        match self {
+            ItemLocation::Local(location) => location.line(),
+            #[cfg(feature = "serde")]
+            ItemLocation::Remote { line, .. } => *line,
+        }
+    }
+
+    /// Source column of the location.
+    fn column(&self) -> u32 {
+        match self {
+            ItemLocation::Local(location) => location.column(),
+            #[cfg(feature = "serde")]
+            ItemLocation::Remote { column, .. } => *column,
+        }
+    }
+}
+
+impl From<&'static Location<'static>> for ItemLocation {
+    fn from(location: &'static Location<'static>) -> Self {
+        Self::Local(location)
+    }
+}
+
+impl Display for ItemLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file(), self.line(), self.column())
+    }
+}
+
+/// Entry of the report context.
+#[derive(Debug)]
+struct ContextEntry {
+    /// Location where the item has been attached.
+    location: ItemLocation,
+    /// Item that has been attached.
+    item: ReportItem,
+    /// Number of times the item has been repeated immediately after itself.
+    repeated: usize,
+    /// Time and thread at which the item has been attached, if recorded.
+    info: Option<Box<timeline::ItemInfo>>,
+    /// Name of the error type, if the entry is the item of an error.
+    ///
+    /// Marks the start of an error layer, i.e., the entries following the item up to
+    /// the next item of an error have been attached to that error.
+    error_type: Option<Cow<'static, str>>,
+}
+
+impl ContextEntry {
+    /// Create an entry for an item attached now.
+    fn new(location: ItemLocation, item: ReportItem) -> Self {
+        Self {
+            location,
+            item,
+            repeated: 0,
+            info: timeline::ItemInfo::capture(),
+            error_type: None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -130,18 +565,203 @@ enum ReportItem {
     Message(String),
     Error,
     Discarded,
+    /// Localized message resolved at render time.
+    Localized(Box<i18n::Message>),
+    /// Named values recorded by the [`fields!`] macro.
+    Fields(Fields),
+    /// Suggestion on how to fix the cause of the error.
+    Help(String),
+    /// Failed assertion recorded by one of the `ensure_*` macros.
+    Assertion(Box<Assertion>),
+    /// Marker for items that have been omitted due to the configured [`Limits`].
+    Omitted(usize),
 }
 
 impl ReportContext {
+    /// Capture a context, including a backtrace and span trace.
+    ///
+    /// Without the `std` feature, backtraces and span traces are not captured.
     #[track_caller]
+    #[must_use]
     pub fn capture() -> Self {
-        let backtrace = Backtrace::capture();
-        let span_trace = SpanTrace::capture();
         Self {
-            backtrace,
-            span_trace,
-            items: vec![(Location::caller(), ReportItem::Error)],
+            #[cfg(feature = "std")]
+            backtrace: Backtrace::capture(),
+            #[cfg(feature = "std")]
+            span_trace: SpanTrace::capture(),
+            #[cfg(feature = "serde")]
+            remote: Vec::new(),
+            prior: Vec::new(),
+            severity: None,
+            items: vec![ContextEntry::new(
+                Location::caller().into(),
+                ReportItem::Error,
+            )],
             error_item: 0,
+            deferred: None,
+        }
+    }
+
+    /// Create a context without capturing a backtrace or span trace.
+    #[track_caller]
+    #[must_use]
+    pub fn light() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            backtrace: Backtrace::disabled(),
+            #[cfg(feature = "std")]
+            span_trace: SpanTrace::new(tracing::Span::none()),
+            #[cfg(feature = "serde")]
+            remote: Vec::new(),
+            prior: Vec::new(),
+            severity: None,
+            items: vec![ContextEntry::new(
+                Location::caller().into(),
+                ReportItem::Error,
+            )],
+            error_item: 0,
+            deferred: None,
+        }
+    }
+
+    /// Backtrace captured when the report has been created.
+    #[cfg(feature = "std")]
+    #[must_use]
+    #[expect(clippy::double_must_use, reason = "consistent with `span_trace`")]
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+
+    /// Span trace captured when the report has been created.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn span_trace(&self) -> &SpanTrace {
+        &self.span_trace
+    }
+
+    /// Push an item, collapsing repetitions and enforcing the configured [`Limits`].
+    fn push(&mut self, location: impl Into<ItemLocation>, item: ReportItem) {
+        let location = match &self.deferred {
+            Some((location, _)) => ItemLocation::Local(location),
+            None => location.into(),
+        };
+        if let Some(last) = self.items.last_mut()
+            && last.location == location
+            && last.item.is_same_message(&item)
+        {
+            last.repeated += 1;
+            return;
+        }
+        let entry = match &self.deferred {
+            Some((_, info)) => ContextEntry {
+                location,
+                item,
+                repeated: 0,
+                info: info.clone(),
+                error_type: None,
+            },
+            None => ContextEntry::new(location, item),
+        };
+        self.items.push(entry);
+        self.enforce_max_items(Limits::current().max_items());
+    }
+
+    /// Record the type of the current error at its item.
+    fn mark_error_layer<E>(&mut self) {
+        if let Some(entry) = self.items.get_mut(self.error_item) {
+            entry.error_type = Some(Cow::Borrowed(core::any::type_name::<E>()));
+        }
+    }
+
+    /// Replace the error item with the materialized error message.
+    fn materialize_error_item(&mut self, item: ReportItem) {
+        let index = self.error_item;
+        self.items[index].item = item;
+        // Collapse the materialized message with the preceding item, if it is the same.
+        if index > 0 && index + 1 == self.items.len() {
+            let (preceding, current) = self.items.split_at_mut(index);
+            let preceding = &mut preceding[index - 1];
+            let current = &current[0];
+            // Only collapse with the item of an error, as the layer of the current error
+            // would otherwise start at an item of the preceding layer.
+            if preceding.location == current.location
+                && preceding.item.is_same_message(&current.item)
+                && preceding.error_type.is_some()
+            {
+                preceding.repeated += current.repeated + 1;
+                self.items.pop();
+                self.error_item = index - 1;
+            }
+        }
+    }
+
+    /// Evict items from the middle until at most `max_items` items are left.
+    ///
+    /// Evicted items are counted by markers, one for each error layer, such that the
+    /// boundaries of the layers are kept.
+    fn enforce_max_items(&mut self, max_items: usize) {
+        let start = max_items / 2;
+        while self.items.len() > max_items {
+            // Never evict markers or the item of the current error.
+            let Some(evict) = (start..self.items.len()).find(|index| {
+                *index != self.error_item
+                    && !matches!(self.items[*index].item, ReportItem::Omitted(_))
+            }) else {
+                // Only the markers of the layers are left.
+                break;
+            };
+            let evicted = self.items.remove(evict);
+            if evict < self.error_item {
+                self.error_item -= 1;
+            }
+            let count = match evicted.item {
+                ReportItem::Discarded => 0,
+                _ => evicted.repeated + 1,
+            };
+            if count == 0 {
+                // Let the layer of an evicted error item start at the next item instead.
+                if let Some(error_type) = evicted.error_type
+                    && let Some(next) = self.items.get_mut(evict)
+                    && next.error_type.is_none()
+                {
+                    next.error_type = Some(error_type);
+                }
+                continue;
+            }
+            // The preceding marker belongs to the same layer, unless the evicted item
+            // starts a layer.
+            if evicted.error_type.is_none()
+                && let Some(preceding) = evict.checked_sub(1).map(|index| &mut self.items[index])
+                && let ReportItem::Omitted(omitted) = &mut preceding.item
+            {
+                *omitted += count;
+            } else {
+                self.items.insert(
+                    evict,
+                    ContextEntry {
+                        location: evicted.location,
+                        item: ReportItem::Omitted(count),
+                        repeated: 0,
+                        info: None,
+                        error_type: evicted.error_type,
+                    },
+                );
+                if evict <= self.error_item {
+                    self.error_item += 1;
+                }
+            }
+        }
+    }
+}
+
+impl ReportItem {
+    /// Check whether both items are the same message.
+    fn is_same_message(&self, other: &ReportItem) -> bool {
+        match (self, other) {
+            (ReportItem::Message(this), ReportItem::Message(other)) => this == other,
+            (ReportItem::Localized(this), ReportItem::Localized(other)) => this == other,
+            (ReportItem::Fields(this), ReportItem::Fields(other)) => this == other,
+            _ => false,
         }
     }
 }
@@ -151,10 +771,64 @@ pub trait Error: Send + Sync + 'static {
     /// Error message.
     fn message(&self) -> Option<&dyn Display>;
 
+    /// Localizable error message.
+    ///
+    /// When rendering a report, the message is translated with the installed
+    /// [`Catalog`][i18n::Catalog]. Without a translation, [`Error::message`] is used.
+    fn localized_message(&self) -> Option<i18n::Message> {
+        None
+    }
+
+    /// Severity of reports with this error.
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    /// Exit code of processes terminating due to reports with this error.
+    ///
+    /// Overrides the [exit code of the severity][Severity::exit_code], unless the
+    /// report has been downgraded to a warning.
+    fn exit_code(&self) -> Option<u8> {
+        None
+    }
+
+    #[cfg_attr(
+        feature = "std",
+        doc = "Error code, e.g., for distinguishing errors in [`metrics`]."
+    )]
+    #[cfg_attr(
+        not(feature = "std"),
+        doc = "Error code, e.g., for distinguishing errors in metrics."
+    )]
+    fn code(&self) -> Option<&str> {
+        None
+    }
+
     /// Try to cast the error into [`StdError`].
     fn as_std_error(&self) -> Option<&dyn StdError> {
         None
     }
+
+    /// Reports nested in reports with this error as pairs of a title and report, e.g.,
+    /// the reports of individual failures.
+    ///
+    /// The nested reports are rendered after the suggestions in the format of the
+    /// enclosing report.
+    fn nested_reports(&self) -> Vec<(String, &dyn NestedReport)> {
+        Vec::new()
+    }
+
+    /// Try to downcast the source of an error to this error type.
+    ///
+    /// Only errors implementing [`StdError`] can be sources.
+    #[doc(hidden)]
+    fn downcast_source<'s>(source: &'s (dyn StdError + 'static)) -> Option<&'s Self>
+    where
+        Self: Sized,
+    {
+        let _ = source;
+        None
+    }
 }
 
 impl<E: StdError + Send + Sync + 'static> Error for E {
@@ -165,18 +839,38 @@ impl<E: StdError + Send + Sync + 'static> Error for E {
     fn as_std_error(&self) -> Option<&dyn StdError> {
         Some(self)
     }
+
+    fn downcast_source<'s>(source: &'s (dyn StdError + 'static)) -> Option<&'s Self> {
+        source.downcast_ref()
+    }
 }
 
 /// Error that can be constructed from arbitrary errors.
 pub trait Whatever: Error {
     fn new() -> Self;
 
+    /// Try to create an error carrying the given message.
+    ///
+    /// Used by [`bail!`] and [`whatever!`]. By default, the message is returned, in
+    /// which case it is attached to the report as context instead. Errors created with
+    /// [`new_whatever_type!`] using `String` as message carry the message.
+    ///
+    /// # Errors
+    ///
+    /// Returns the message if the error cannot carry it.
+    fn try_with_message(message: String) -> Result<Self, String>
+    where
+        Self: Sized,
+    {
+        Err(message)
+    }
+
     #[track_caller]
     fn propagate<E: Error>(report: Report<E>) -> Report<Self>
     where
         Self: Sized,
     {
-        report.propagate_map(|_| Self::new())
+        report.propagate_retaining(Self::new())
     }
 }
 
@@ -188,7 +882,7 @@ macro_rules! new_whatever_type {
         $vis struct $name(());
 
         impl $crate::Error for $name {
-            fn message(&self) -> Option<&dyn ::std::fmt::Display> {
+            fn message(&self) -> Option<&dyn ::core::fmt::Display> {
                 None
             }
         }
@@ -206,7 +900,7 @@ macro_rules! new_whatever_type {
         $vis struct $name(());
 
         impl $crate::Error for $name {
-            fn message(&self) -> Option<&dyn ::std::fmt::Display> {
+            fn message(&self) -> Option<&dyn ::core::fmt::Display> {
                 Some(&$message)
             }
         }
@@ -217,25 +911,258 @@ macro_rules! new_whatever_type {
             }
         }
     };
+
+    ($(#[$meta:meta])* $vis:vis $name:ident (String)) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        $vis struct $name(::core::option::Option<$crate::__private::String>);
+
+        impl $crate::Error for $name {
+            fn message(&self) -> Option<&dyn ::core::fmt::Display> {
+                self.0.as_ref().map(|message| message as &dyn ::core::fmt::Display)
+            }
+        }
+
+        impl $crate::Whatever for $name {
+            fn new() -> Self {
+                $name(::core::option::Option::None)
+            }
+
+            fn try_with_message(
+                message: $crate::__private::String,
+            ) -> ::core::result::Result<Self, $crate::__private::String> {
+                ::core::result::Result::Ok($name(::core::option::Option::Some(message)))
+            }
+        }
+    };
 }
 
+/// Define an error enum whose variants carry a fixed message.
+///
+/// The generated enum implements [`Display`] and [`core::error::Error`] and, hence,
+/// [`Error`]. Errors can be propagated as one of its variants with
+/// [`ResultExt::whatever_as`].
+///
+/// ```
+/// # use reportify::{Report, ResultExt};
+/// reportify::new_error_enum! {
+///     /// Error loading a project.
+///     pub enum LoadError {
+///         /// Project has not been found.
+///         NotFound("project not found"),
+///         /// Project is invalid.
+///         Invalid("invalid project"),
+///     }
+/// }
+///
+/// fn load(path: &str) -> Result<String, Report<LoadError>> {
+///     std::fs::read_to_string(path).whatever_as(LoadError::NotFound)
+/// }
+///
+/// let report = load("does-not-exist.json").unwrap_err();
+/// assert_eq!(report.error(), &LoadError::NotFound);
+/// ```
+#[macro_export]
+macro_rules! new_error_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident ($message:literal)
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+        }
+
+        impl $name {
+            /// Message of the error.
+            #[must_use]
+            pub const fn message(&self) -> &'static str {
+                match self {
+                    $(
+                        Self::$variant => $message,
+                    )*
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.message())
+            }
+        }
+
+        impl ::core::error::Error for $name {}
+    };
+}
+
+/// Return early with a report created by [`whatever!`].
+///
+/// ```
+/// # use reportify::{Report, bail};
+/// # reportify::new_whatever_type!(ConfigError);
+/// fn check_port(port: u16) -> Result<(), Report<ConfigError>> {
+///     if port < 1024 {
+///         bail!("port {port} is privileged", help = "use a port above 1023");
+///     }
+///     Ok(())
+/// }
+/// ```
 #[macro_export]
 macro_rules! bail {
     ($($arg:tt)*) => {
-        return $crate::ResultExt::context(Err({
-            let error = $crate::Whatever::new();
-            $crate::Report::new(error, $crate::ReportContext::capture())
-        }), || format!($($arg)*));
+        return ::core::result::Result::Err($crate::whatever!($($arg)*))
     };
 }
 
+/// Create a report at the location of the macro invocation.
+///
+/// The arguments take one of the following forms, each optionally followed by a
+/// suggestion `help = "format {args}"`, which is formatted like the message and
+/// attached as [`Help`]:
+///
+/// - `whatever!("format {args}")` creates a report with a [`Whatever`] error and the
+///   formatted message. The message becomes the error message, if the error carries a
+///   message, and is attached as context otherwise.
+/// - `whatever!(error, "format {args}")` creates a report with the given error and
+///   attaches the formatted message as context.
+/// - `whatever!(error)` creates a report with the given error.
+///
+/// The error is converted via [`Into`] into the error of the report. As for
+/// [`format!`][alloc::format], the format strings are checked at compile time. The
+/// suggestion may also be given as an expression evaluating to a string, e.g.,
+/// `help = message`, in which case it is used as is without formatting.
+///
+/// Note that a named argument `help` is always interpreted as the suggestion, i.e., it
+/// cannot be used as a format argument of the message. To find the suggestion, the
+/// macro processes its arguments token by token, with each token counting towards the
+/// [recursion limit](https://doc.rust-lang.org/reference/attributes/limits.html). For
+/// very long argument lists, the limit may have to be raised.
+///
+/// ```
+/// # use reportify::{Report, whatever};
+/// # reportify::new_error_enum! {
+/// #     pub enum ConfigError {
+/// #         InvalidPort("invalid port"),
+/// #     }
+/// # }
+/// fn parse_port(port: &str) -> Result<u16, Report<ConfigError>> {
+///     port.parse().map_err(|_| {
+///         whatever!(
+///             ConfigError::InvalidPort,
+///             "parsing {port:?}",
+///             help = "use a number between {} and {}",
+///             u16::MIN,
+///             u16::MAX,
+///         )
+///     })
+/// }
+/// ```
 #[macro_export]
 macro_rules! whatever {
+    (@args [$($args:tt)*] help = $format:literal $($help_args:tt)*) => {
+        $crate::whatever!(
+            @report [$($args)*]
+            ::core::option::Option::Some($crate::Help::new(
+                $crate::__private::format!($format $($help_args)*)
+            ))
+        )
+    };
+    (@args [$($args:tt)*] help = $help:expr $(,)?) => {
+        $crate::whatever!(@report [$($args)*] ::core::option::Option::Some($crate::Help::new($help)))
+    };
+    (@args [$($args:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::whatever!(@args [$($args)* $next] $($rest)*)
+    };
+    (@args [$($args:tt)*]) => {
+        $crate::whatever!(@report [$($args)*] ::core::option::Option::None)
+    };
+    (@report [$format:literal $($args:tt)*] $help:expr) => {
+        $crate::__private::whatever($crate::__private::format!($format $($args)*), $help)
+    };
+    (@report [$error:expr $(,)?] $help:expr) => {
+        $crate::__private::report($error, ::core::option::Option::None, $help)
+    };
+    (@report [$error:expr, $format:literal $($args:tt)*] $help:expr) => {
+        $crate::__private::report(
+            $error,
+            ::core::option::Option::Some($crate::__private::format!($format $($args)*)),
+            $help,
+        )
+    };
     ($($arg:tt)*) => {
-        {
-            let error = $crate::Whatever::new();
-            $crate::Report::new(error, $crate::ReportContext::capture()).with_context(format!($($arg)*))
-        }
+        $crate::whatever!(@args [] $($arg)*)
+    };
+}
+
+/// Record the values of variables as structured [`Fields`].
+///
+/// Fields are specified like for [`tracing`](https://docs.rs/tracing)'s macros. A
+/// variable records its [`Display`] representation under its name, `?variable` its
+/// [`Debug`][core::fmt::Debug] representation, and `name = value`, `name = %value`, or
+/// `name = ?value` record the given expression under the given name:
+///
+/// ```
+/// # use reportify::{Report, ResultExt};
+/// # reportify::new_whatever_type!(LoadError);
+/// fn load(path: &str, retries: u32) -> Result<String, Report<LoadError>> {
+///     std::fs::read_to_string(path)
+///         .whatever()
+///         .context(reportify::fields!(path, ?retries, attempt = retries + 1))
+/// }
+/// ```
+///
+/// The macro creates a closure, such that the values are only recorded in case an
+/// error occurs. Values are borrowed and, hence, formatted when the fields are
+/// attached, e.g., `path = %path.display()`. Prefixing an expression with `move`, e.g.,
+/// `attempt = move retries + 1` or `config = move ?config.clone()`, moves the value
+/// into the fields instead, such that it is only formatted when the report is
+/// rendered. Moved values must be owned, i.e., [`Send`], [`Sync`], and `'static`.
+///
+/// This macro is not called `context!` to avoid a clash with the [`context`]
+/// attribute, which lives in the same namespace.
+#[macro_export]
+macro_rules! fields {
+    (@fields $fields:expr;) => {
+        $fields
+    };
+    (@fields $fields:expr; $name:ident = move ?$value:expr $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields $fields.debug_value(::core::stringify!($name), $value); $($($rest)*)?)
+    };
+    (@fields $fields:expr; $name:ident = move %$value:expr $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields $fields.value(::core::stringify!($name), $value); $($($rest)*)?)
+    };
+    (@fields $fields:expr; $name:ident = move $value:expr $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields $fields.value(::core::stringify!($name), $value); $($($rest)*)?)
+    };
+    (@fields $fields:expr; $name:ident = ?$value:expr $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields $fields.debug_field(::core::stringify!($name), &$value); $($($rest)*)?)
+    };
+    (@fields $fields:expr; $name:ident = %$value:expr $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields $fields.field(::core::stringify!($name), &$value); $($($rest)*)?)
+    };
+    (@fields $fields:expr; $name:ident = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields $fields.field(::core::stringify!($name), &$value); $($($rest)*)?)
+    };
+    (@fields $fields:expr; ?$name:ident $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields $fields.debug_field(::core::stringify!($name), &$name); $($($rest)*)?)
+    };
+    (@fields $fields:expr; %$name:ident $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields $fields.field(::core::stringify!($name), &$name); $($($rest)*)?)
+    };
+    (@fields $fields:expr; $name:ident $(, $($rest:tt)*)?) => {
+        $crate::fields!(@fields $fields.field(::core::stringify!($name), &$name); $($($rest)*)?)
+    };
+    ($($fields:tt)*) => {
+        || $crate::fields!(@fields $crate::Fields::new(); $($fields)*)
     };
 }
 
@@ -248,6 +1175,102 @@ macro_rules! ensure {
     };
 }
 
+/// Ensure that two values are equal, bailing with a [`Whatever`] error otherwise.
+///
+/// The values are recorded via their [`Debug`][core::fmt::Debug] representation as part
+/// of the report context. Long values are rendered as a line diff.
+#[macro_export]
+macro_rules! ensure_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left == *right) {
+                    return ::core::result::Result::Err(
+                        $crate::__private::ensure_eq_failed(left, right, ::core::option::Option::None),
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($args:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left == *right) {
+                    return ::core::result::Result::Err($crate::__private::ensure_eq_failed(
+                        left,
+                        right,
+                        ::core::option::Option::Some(::core::format_args!($($args)+)),
+                    ));
+                }
+            }
+        }
+    };
+}
+
+/// Ensure that two values are not equal, bailing with a [`Whatever`] error otherwise.
+///
+/// The values are recorded via their [`Debug`][core::fmt::Debug] representation as part
+/// of the report context.
+#[macro_export]
+macro_rules! ensure_ne {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if *left == *right {
+                    return ::core::result::Result::Err(
+                        $crate::__private::ensure_ne_failed(left, right, ::core::option::Option::None),
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($args:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if *left == *right {
+                    return ::core::result::Result::Err($crate::__private::ensure_ne_failed(
+                        left,
+                        right,
+                        ::core::option::Option::Some(::core::format_args!($($args)+)),
+                    ));
+                }
+            }
+        }
+    };
+}
+
+/// Ensure that a value matches a pattern, bailing with a [`Whatever`] error otherwise.
+///
+/// The value is recorded via its [`Debug`][core::fmt::Debug] representation as part of
+/// the report context.
+#[macro_export]
+macro_rules! ensure_matches {
+    ($value:expr, $pattern:pat $(if $guard:expr)? $(,)?) => {
+        match $value {
+            $pattern $(if $guard)? => {}
+            ref value => {
+                return ::core::result::Result::Err($crate::__private::ensure_matches_failed(
+                    value,
+                    ::core::stringify!($pattern $(if $guard)?),
+                    ::core::option::Option::None,
+                ));
+            }
+        }
+    };
+    ($value:expr, $pattern:pat $(if $guard:expr)?, $($args:tt)+) => {
+        match $value {
+            $pattern $(if $guard)? => {}
+            ref value => {
+                return ::core::result::Result::Err($crate::__private::ensure_matches_failed(
+                    value,
+                    ::core::stringify!($pattern $(if $guard)?),
+                    ::core::option::Option::Some(::core::format_args!($($args)+)),
+                ));
+            }
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! return_error {
     ($expr:expr) => {
@@ -258,6 +1281,105 @@ macro_rules! return_error {
     };
 }
 
+/// Implementation details used by the macros of this crate.
+#[doc(hidden)]
+pub mod __private {
+    use alloc::boxed::Box;
+    pub use alloc::format;
+    pub use alloc::string::String;
+    use alloc::string::ToString;
+    use core::fmt::{Arguments, Debug};
+    use core::panic::Location;
+
+    use crate::ensure::Assertion;
+    use crate::{Error, Help, Report, ReportContext, ReportItem, Whatever};
+
+    /// Create a report with the given message for [`bail!`] and [`whatever!`].
+    ///
+    /// The message becomes the error message, if supported by the error, and is
+    /// attached as context otherwise.
+    #[must_use]
+    #[track_caller]
+    pub fn whatever<E: Whatever>(message: String, help: Option<Help>) -> Report<E> {
+        let report = match E::try_with_message(message) {
+            Ok(error) => Report::new(error, ReportContext::capture()),
+            Err(message) => {
+                let mut context = ReportContext::capture();
+                context.push(Location::caller(), ReportItem::Message(message));
+                Report::new(E::new(), context)
+            }
+        };
+        match help {
+            Some(help) => report.with_context(help),
+            None => report,
+        }
+    }
+
+    /// Create a report with the given error and message for [`bail!`] and
+    /// [`whatever!`].
+    #[must_use]
+    #[track_caller]
+    pub fn report<E: Error>(
+        error: impl Into<E>,
+        message: Option<String>,
+        help: Option<Help>,
+    ) -> Report<E> {
+        let mut context = ReportContext::capture();
+        if let Some(message) = message {
+            context.push(Location::caller(), ReportItem::Message(message));
+        }
+        let report = Report::new(error.into(), context);
+        match help {
+            Some(help) => report.with_context(help),
+            None => report,
+        }
+    }
+
+    /// Create a report for a failed assertion.
+    #[track_caller]
+    fn assertion_failed<E: Whatever>(
+        assertion: Assertion,
+        message: Option<Arguments<'_>>,
+    ) -> Report<E> {
+        let mut context = ReportContext::capture();
+        context.push(
+            Location::caller(),
+            ReportItem::Assertion(Box::new(assertion)),
+        );
+        if let Some(message) = message {
+            context.push(Location::caller(), ReportItem::Message(message.to_string()));
+        }
+        Report::new(E::new(), context)
+    }
+
+    #[track_caller]
+    pub fn ensure_eq_failed<E: Whatever>(
+        left: &dyn Debug,
+        right: &dyn Debug,
+        message: Option<Arguments<'_>>,
+    ) -> Report<E> {
+        assertion_failed(Assertion::eq(left, right), message)
+    }
+
+    #[track_caller]
+    pub fn ensure_ne_failed<E: Whatever>(
+        left: &dyn Debug,
+        right: &dyn Debug,
+        message: Option<Arguments<'_>>,
+    ) -> Report<E> {
+        assertion_failed(Assertion::ne(left, right), message)
+    }
+
+    #[track_caller]
+    pub fn ensure_matches_failed<E: Whatever>(
+        value: &dyn Debug,
+        pattern: &'static str,
+        message: Option<Arguments<'_>>,
+    ) -> Report<E> {
+        assertion_failed(Assertion::matches(value, pattern), message)
+    }
+}
+
 /// Context that can be attached to a report.
 pub trait Context<E> {
     /// Attach this context to the given report.
@@ -287,8 +1409,76 @@ impl<E> Context<E> for String {
     fn attach_to(self, report: &mut Report<E>) {
         report
             .context
-            .items
-            .push((Location::caller(), ReportItem::Message(self)))
+            .push(Location::caller(), ReportItem::Message(self));
+    }
+}
+
+/// Suggestion on how to fix the cause of an error.
+///
+/// Suggestions can be attached to reports as context:
+///
+/// ```
+/// # use reportify::{Help, Report, ResultExt};
+/// fn read_config() -> Result<String, Report<std::io::Error>> {
+///     std::fs::read_to_string("config.toml")
+///         .context(Help::new("create a `config.toml` file in the current directory"))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Help(String);
+
+impl Help {
+    /// Create a new suggestion.
+    pub fn new(suggestion: impl Into<String>) -> Self {
+        Self(suggestion.into())
+    }
+}
+
+impl<E> Context<E> for Help {
+    #[track_caller]
+    fn attach_to(self, report: &mut Report<E>) {
+        report
+            .context
+            .push(Location::caller(), ReportItem::Help(self.0));
+    }
+}
+
+/// Run the given function attaching the given context to any report escaping it.
+///
+/// The context is attached with the location of the call to [`scope`]. This includes
+/// reports that are created implicitly by the `?` operator.
+///
+/// ```
+/// # use reportify::{Report, ResultExt};
+/// # reportify::new_whatever_type!(LoadError);
+/// fn load(path: &str) -> Result<String, Report<LoadError>> {
+///     reportify::scope(
+///         || format!("loading project at {path:?}"),
+///         || {
+///             let content = std::fs::read_to_string(path).whatever()?;
+///             Ok(content)
+///         },
+///     )
+/// }
+/// ```
+///
+/// See also the [`context`][macro@context] attribute.
+///
+/// # Errors
+///
+/// Fails with the report of the function, if any, with the context attached.
+#[track_caller]
+pub fn scope<T, E, C, F>(context: C, f: F) -> Result<T, Report<E>>
+where
+    C: Context<E>,
+    F: FnOnce() -> Result<T, Report<E>>,
+{
+    match f() {
+        Ok(value) => Ok(value),
+        Err(mut report) => {
+            context.attach_to(&mut report);
+            Err(report)
+        }
     }
 }
 
@@ -349,6 +1539,13 @@ pub trait ResultExt {
     /// Propagate the error using [`Whatever`] to construct the new error.
     fn whatever<F: Whatever>(self) -> Result<Self::Value, Report<F>>;
 
+    /// Propagate the error as the given error.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the propagated report if the result is an error.
+    fn whatever_as<F: Error>(self, error: F) -> Result<Self::Value, Report<F>>;
+
     /// Assert that the result is [`Ok`] according to a program invariant.
     ///
     /// Only use this in case an error is a bug in the program, not an external error.
@@ -404,6 +1601,11 @@ impl<T, E: Error> ResultExt for Result<T, E> {
         self.report().whatever()
     }
 
+    #[track_caller]
+    fn whatever_as<F: Error>(self, error: F) -> Result<Self::Value, Report<F>> {
+        self.report().whatever_as(error)
+    }
+
     #[track_caller]
     fn assert_ok(self) -> Self::Value {
         self.report().assert_ok()
@@ -475,6 +1677,14 @@ impl<T, E: Error> ResultExt for Result<T, Report<E>> {
         }
     }
 
+    #[track_caller]
+    fn whatever_as<F: Error>(self, error: F) -> Result<Self::Value, Report<F>> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(report) => Err(report.whatever_as(error)),
+        }
+    }
+
     #[track_caller]
     fn assert_ok(self) -> Self::Value {
         match self {
@@ -490,7 +1700,7 @@ impl<T, E: Error> ResultExt for Result<T, Report<E>> {
         match self {
             Ok(value) => Some(value),
             Err(report) => {
-                tracing::error!("ignoring error\n\n{report}");
+                report.log_ignored();
                 None
             }
         }
@@ -499,14 +1709,17 @@ impl<T, E: Error> ResultExt for Result<T, Report<E>> {
     #[track_caller]
     fn ignore(self) {
         if let Err(report) = self {
-            tracing::error!("ignoring error\n\n{report}");
+            report.log_ignored();
         }
     }
 }
 
+#[cfg(test)]
+extern crate self as reportify;
+
 #[cfg(test)]
 mod tests {
-    use crate::{Report, ResultExt};
+    use crate::{LightReport, Report, ReportItem, ResultExt};
 
     new_whatever_type!(pub TestError("test error"));
 
@@ -523,13 +1736,454 @@ mod tests {
         .whatever()
     }
 
+    new_whatever_type!(MessageError(String));
+
+    fn example_bail_message() -> Result<(), Report<MessageError>> {
+        let x = 1;
+        bail!("test {x}");
+    }
+
     #[test]
     fn test_bail() {
         assert!(example_bail().is_err());
     }
 
+    #[test]
+    fn test_bail_message() {
+        let rendered = example_bail().expect_err("should fail").to_string();
+        assert!(rendered.starts_with("test error\n"));
+        assert!(rendered.contains(": test 1\n"));
+        let rendered = example_bail_message().expect_err("should fail").to_string();
+        assert!(rendered.starts_with("test 1\n"));
+        assert_eq!(rendered.matches("test 1").count(), 2);
+    }
+
+    crate::new_error_enum! {
+        enum ExampleError {
+            InvalidPort("invalid port"),
+        }
+    }
+
+    fn example_bail_error(port: &str) -> Result<u16, Report<ExampleError>> {
+        match port.parse() {
+            Ok(port) => Ok(port),
+            Err(_) => bail!(
+                ExampleError::InvalidPort,
+                "parsing {port:?}",
+                help = "use a number between 0 and 65535",
+            ),
+        }
+    }
+
+    #[test]
+    fn test_bail_error() {
+        let report = example_bail_error("http").expect_err("should fail");
+        assert_eq!(report.error(), &ExampleError::InvalidPort);
+        let rendered = report.to_string();
+        assert!(rendered.starts_with("invalid port\n"));
+        assert!(rendered.contains(": parsing \"http\"\n"));
+        assert!(rendered.contains("help: use a number between 0 and 65535\n"));
+        // The error, the message, and the suggestion are recorded at the call site.
+        let lines = report
+            .context
+            .items
+            .iter()
+            .map(|entry| entry.location.line())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| *line == lines[0]));
+        let report: Report<ExampleError> =
+            whatever!(ExampleError::InvalidPort, help = "check the port");
+        assert!(report.to_string().contains("help: check the port\n"));
+        let rendered = (|| -> Result<(), Report<TestError>> {
+            let x = 2;
+            bail!("test {x}", help = "use {x} instead");
+        })()
+        .expect_err("should fail")
+        .to_string();
+        assert!(rendered.contains(": test 2\n"));
+        assert!(rendered.contains("help: use 2 instead\n"));
+        let help = String::from("use {x} literally");
+        let report: Report<ExampleError> = whatever!(ExampleError::InvalidPort, help = help);
+        assert!(report.to_string().contains("help: use {x} literally\n"));
+        let report: Report<ExampleError> =
+            whatever!(ExampleError::InvalidPort, help = "use {} instead", 3,);
+        assert!(report.to_string().contains("help: use 3 instead\n"));
+    }
+
     #[test]
     fn test_propagate_whatever() {
         assert!(example_propagate_whatever().is_err());
     }
+
+    fn example_ensure(x: i32) -> Result<(), Report<TestError>> {
+        ensure_eq!(x % 2, 0, "{x} is not even");
+        ensure_ne!(x, 4);
+        ensure_matches!(Some(x), Some(y) if y < 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ensure() {
+        assert!(example_ensure(2).is_ok());
+        let report = example_ensure(3).expect_err("should fail");
+        let rendered = report.to_string();
+        assert!(rendered.contains("assertion `left == right` failed\n   left: 1\n  right: 0"));
+        assert!(rendered.contains("3 is not even"));
+        let report = example_ensure(4).expect_err("should fail");
+        assert!(
+            report
+                .to_string()
+                .contains("assertion `left != right` failed")
+        );
+        let report = example_ensure(12).expect_err("should fail");
+        assert!(
+            report
+                .to_string()
+                .contains("assertion `value matches Some(y) if y < 10` failed\n  value: Some(12)")
+        );
+    }
+
+    new_error_enum! {
+        enum LoadError {
+            NotFound("not found"),
+            Invalid("invalid"),
+        }
+    }
+
+    fn example_whatever_as(value: &str) -> Result<i32, Report<LoadError>> {
+        if value.is_empty() {
+            Err(LoadError::NotFound)?;
+        }
+        value.parse::<i32>().whatever_as(LoadError::Invalid)
+    }
+
+    #[test]
+    fn test_whatever_as() {
+        assert!(example_whatever_as("42").is_ok());
+        let report = example_whatever_as("x").expect_err("should fail");
+        assert_eq!(report.error(), &LoadError::Invalid);
+        assert_eq!(LoadError::NotFound.to_string(), "not found");
+        assert!(report.to_string().starts_with("invalid\n"));
+    }
+
+    #[crate::context("parsing {value:?}")]
+    fn example_context_attribute(value: &str) -> Result<i32, Report<TestError>> {
+        if value.is_empty() {
+            return Ok(0);
+        }
+        let value = value.parse::<i32>().whatever()?;
+        Ok(value)
+    }
+
+    #[crate::context(move, "consuming {value}")]
+    fn example_context_attribute_move(value: String) -> Result<(), Report<TestError>> {
+        drop(value);
+        bail!("consumed");
+    }
+
+    #[crate::context("running asynchronously")]
+    async fn example_context_attribute_async() -> Result<(), Report<TestError>> {
+        bail!("failed");
+    }
+
+    #[test]
+    fn test_scope() {
+        let report = crate::scope("in scope", || -> Result<(), Report<TestError>> {
+            Err(std::io::Error::other("some error")).whatever()?;
+            Ok(())
+        })
+        .expect_err("should fail");
+        assert!(report.to_string().contains("in scope"));
+        assert!(example_context_attribute("").is_ok());
+        let report = example_context_attribute("x").expect_err("should fail");
+        // The context is attached with the location of the attribute.
+        assert!(report.to_string().contains(":5: parsing \"x\""));
+        let report = example_context_attribute_move("value".to_owned()).expect_err("should fail");
+        assert!(report.to_string().contains("consuming value"));
+        let future = std::pin::pin!(example_context_attribute_async());
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        let std::task::Poll::Ready(result) = future.poll(&mut cx) else {
+            panic!("future should be ready");
+        };
+        let report = result.expect_err("should fail");
+        assert!(report.to_string().contains("running asynchronously"));
+    }
+
+    fn example_light(value: &str) -> Result<i32, LightReport<std::num::ParseIntError>> {
+        value
+            .parse()
+            .map_err(|error| Report::light(error).with_context("parsing value"))
+    }
+
+    fn example_upgrade() -> Result<i32, Report<std::num::ParseIntError>> {
+        Ok(example_light("x")?)
+    }
+
+    #[test]
+    fn test_light_report() {
+        let report = example_light("x").expect_err("should fail");
+        assert_eq!(report.error().kind(), &std::num::IntErrorKind::InvalidDigit);
+        assert_eq!(report.to_string(), "invalid digit found in string");
+        let report = example_upgrade().expect_err("should fail");
+        let rendered = report.to_string();
+        assert!(rendered.contains("parsing value"));
+        let report = example_light("x")
+            .map_err(|report| {
+                report.propagate_map(|_| MessageError(Some("invalid value".to_owned())))
+            })
+            .map_err(|report| Report::from(report.with_context("handling value")))
+            .expect_err("should fail");
+        let rendered = report.render_layered();
+        assert!(rendered.contains("parsing value"), "{rendered}");
+        assert!(rendered.contains("handling value"), "{rendered}");
+    }
+
+    #[test]
+    fn test_light_report_unsync_context() {
+        let attempt = std::cell::Cell::new(3);
+        let report = example_light("x")
+            .map_err(|report| report.with_context(move || format!("attempt {}", attempt.get())))
+            .expect_err("should fail");
+        let rendered = report.upgrade().to_string();
+        assert!(rendered.contains("attempt 3"), "{rendered}");
+    }
+
+    /// Lines of the context of a rendered report without the locations of the items.
+    fn layered_context(rendered: &str) -> Vec<String> {
+        rendered
+            .lines()
+            .skip_while(|line| !line.is_empty())
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .map(|line| match line.rsplit_once(": ") {
+                Some((location, message)) if location.starts_with("  ") => {
+                    format!("  {message}")
+                }
+                _ => line.to_owned(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_layered() {
+        let report = example_propagate_whatever()
+            .context("loading data")
+            .whatever::<MessageError>()
+            .expect_err("should fail")
+            .with_context("running example");
+        let rendered = report.render_layered();
+        assert_eq!(
+            layered_context(&rendered),
+            [
+                "reportify::tests::MessageError:",
+                "  running example",
+                "reportify::tests::TestError:",
+                "  loading data",
+                "  test error",
+                "std::io::error::Error:",
+                "  file not found",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_layered_omitted() {
+        let mut report = example_propagate_whatever()
+            .context("loading data")
+            .whatever::<MessageError>()
+            .context("parsing config")
+            .context("reading config")
+            .whatever::<TestError>()
+            .expect_err("should fail")
+            .with_context("running example");
+        // Evicts the item of `MessageError`, which is not rendered and not counted, and
+        // the items attached to it.
+        report.context.enforce_max_items(6);
+        let rendered = report.render_layered();
+        assert_eq!(
+            layered_context(&rendered),
+            [
+                "reportify::tests::TestError:",
+                "  running example",
+                "  test error",
+                "reportify::tests::MessageError:",
+                "  … 2 context items omitted",
+                "reportify::tests::TestError:",
+                "  loading data",
+                "  test error",
+                "std::io::error::Error:",
+                "  file not found",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_layered_formats() {
+        let report = example_propagate_whatever()
+            .context("loading data")
+            .whatever::<MessageError>()
+            .expect_err("should fail")
+            .with_context("running example");
+        let rendered = report
+            .render_layered_with(crate::render::Markdown::new())
+            .expect("should render");
+        assert!(
+            rendered.contains("\n- `reportify::tests::MessageError`\n  - `"),
+            "{rendered}"
+        );
+        assert!(rendered.contains("`: loading data\n  - `"), "{rendered}");
+        assert!(rendered.contains("`: file not found\n"), "{rendered}");
+        let rendered = report
+            .render_layered_with(crate::render::Html)
+            .expect("should render");
+        assert!(
+            rendered.contains(
+                "<li class=\"layer\"><code>reportify::tests::MessageError</code>\n<ul>\n<li><code>"
+            ),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("</code>: file not found</li>\n</ul>\n</li>\n</ul>\n"),
+            "{rendered}"
+        );
+    }
+
+    /// Render the report in the given format, truncated before the first occurrence of
+    /// the given needle.
+    fn render_truncated_before(
+        report: &Report<MessageError>,
+        format: &mut dyn crate::render::Format,
+        needle: &str,
+    ) -> String {
+        let mut full = String::new();
+        report
+            .render(&mut full, format, true)
+            .expect("should render");
+        let max_len = full.find(needle).expect("should contain needle");
+        let mut truncated = String::new();
+        let mut format = crate::limits::TruncatingFormat::new(format, max_len);
+        report
+            .render(&mut truncated, &mut format, true)
+            .expect("should render");
+        truncated
+    }
+
+    #[test]
+    fn test_render_truncated_formats() {
+        let report = example_propagate_whatever()
+            .context("loading data")
+            .whatever::<MessageError>()
+            .expect_err("should fail");
+        let rendered =
+            render_truncated_before(&report, &mut crate::render::Markdown::new(), "file not");
+        assert!(rendered.contains("`: loading data\n"), "{rendered}");
+        assert!(!rendered.contains("file not found"), "{rendered}");
+        assert!(
+            rendered.ends_with(" bytes omitted)*\n") && rendered.contains("\n\n*… report"),
+            "{rendered}"
+        );
+        let rendered = render_truncated_before(&report, &mut crate::render::Html, "file not");
+        assert!(
+            rendered.contains("</code>: loading data</li>\n"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("file not found"), "{rendered}");
+        assert_eq!(
+            rendered.matches("<ul").count(),
+            rendered.matches("</ul>").count(),
+            "{rendered}"
+        );
+        assert_eq!(
+            rendered.matches("<li").count(),
+            rendered.matches("</li>").count(),
+            "{rendered}"
+        );
+        assert!(
+            rendered.ends_with(" bytes omitted)</p>\n</div>\n"),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn test_collapse_repeated_context() {
+        let mut report = example_bail().expect_err("should fail");
+        for _ in 0..37 {
+            report = report.with_context("retrying");
+        }
+        assert_eq!(report.context.items.len(), 3);
+        assert!(
+            report
+                .to_string()
+                .contains("retrying (… repeated 37 times)")
+        );
+    }
+
+    #[test]
+    fn test_enforce_max_items() {
+        let mut report = example_bail().expect_err("should fail");
+        for i in 0..10 {
+            report = report.with_context(format!("item {i}"));
+        }
+        report.context.enforce_max_items(6);
+        assert_eq!(report.context.items.len(), 6);
+        assert!(matches!(
+            report.context.items[3].item,
+            ReportItem::Omitted(7)
+        ));
+        assert!(matches!(
+            report.context.items[report.context.error_item].item,
+            ReportItem::Error
+        ));
+        assert!(report.to_string().contains("… 7 context items omitted"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_localization() {
+        #[derive(Debug)]
+        struct ConfigError(&'static str);
+
+        impl crate::Error for ConfigError {
+            fn message(&self) -> Option<&dyn std::fmt::Display> {
+                Some(&"invalid configuration")
+            }
+
+            fn localized_message(&self) -> Option<crate::i18n::Message> {
+                Some(
+                    crate::i18n::Message::new("test-config-invalid", "invalid configuration")
+                        .arg("path", self.0),
+                )
+            }
+        }
+
+        /// Uninstall the catalog when dropped, even if the test fails.
+        struct Uninstall;
+
+        impl Drop for Uninstall {
+            fn drop(&mut self) {
+                crate::i18n::uninstall();
+            }
+        }
+
+        let _uninstall = Uninstall;
+        crate::i18n::install(
+            crate::i18n::Translations::new()
+                .with("test-config-invalid", "ungültige Konfiguration { $path }")
+                .with("test-loading", "Laden von { $path }"),
+        );
+        let report = Report::<ConfigError>::from(ConfigError("a.toml"))
+            .with_context(
+                crate::i18n::Message::new("test-loading", "loading").arg("path", "a.toml"),
+            )
+            .with_context(crate::i18n::Message::new(
+                "test-untranslated",
+                "untranslated",
+            ))
+            .whatever::<TestError>();
+        let rendered = report.to_string();
+        assert!(rendered.contains(": ungültige Konfiguration a.toml\n"));
+        assert!(rendered.contains(": Laden von a.toml\n"));
+        assert!(rendered.contains(": untranslated\n"));
+    }
 }
@@ -0,0 +1,163 @@
+//! Lightweight reports for errors that are handled rather than reported.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::panic::Location;
+
+use crate::i18n::ErrorMessage;
+use crate::{Context, ContextEntry, Error, Limits, Report, ReportContext, ReportItem, timeline};
+
+/// Context attached to a lightweight report, recorded when the report is upgraded.
+type Deferred<E> = Box<dyn FnOnce(&mut Report<E>) + Send>;
+
+/// Lightweight report for errors that are handled rather than reported.
+///
+/// Capturing a backtrace and a span trace is expensive and not necessary in case an
+/// error is handled. A [`LightReport`] stores only the error and the context attached
+/// to it, deferring the recording of the context until the report is needed. If the
+/// error turns out to be reported after all, the report can be
+/// [upgraded][LightReport::upgrade] to a full [`Report`], capturing the backtrace and
+/// span trace at the point of the upgrade. Using `?` to propagate a [`LightReport<E>`]
+/// as a [`Report<E>`] upgrades the report implicitly.
+///
+/// Displaying a lightweight report shows only the message of the error.
+///
+/// As the context may not be shareable between threads, a [`LightReport`] is [`Send`]
+/// but not [`Sync`].
+pub struct LightReport<E> {
+    error: E,
+    /// Entries of the errors the report has been propagated from.
+    items: Vec<ContextEntry>,
+    /// Location at which the current error has been created or propagated.
+    location: &'static Location<'static>,
+    /// Time and thread at which the current error has been created or propagated.
+    info: Option<Box<timeline::ItemInfo>>,
+    /// Context attached to the current error.
+    deferred: Vec<Deferred<E>>,
+}
+
+impl<E: Error> LightReport<E> {
+    /// Create a new lightweight report from the given error.
+    #[track_caller]
+    pub fn new(error: E) -> Self {
+        #[cfg(feature = "std")]
+        crate::metrics::record(&error, crate::metrics::Event::Created);
+        Self {
+            error,
+            items: Vec::new(),
+            location: Location::caller(),
+            info: timeline::ItemInfo::capture(),
+            deferred: Vec::new(),
+        }
+    }
+
+    /// Underlying error.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Consume the report and return the underlying error.
+    pub fn into_error(self) -> E {
+        self.error
+    }
+
+    /// Attach the given context to the report.
+    ///
+    /// The context is recorded only when the report is upgraded or propagated. Unlike
+    /// [`Report::with_context`], the context is stored until then and must therefore be
+    /// [`Send`] and `'static`. Borrowed context must be formatted before attaching it,
+    /// e.g., with [`format!`][alloc::format].
+    #[must_use]
+    #[track_caller]
+    pub fn with_context<C: Context<E> + Send + 'static>(mut self, context: C) -> Self {
+        let location = Location::caller();
+        let info = timeline::ItemInfo::capture();
+        self.deferred.push(Box::new(move |report: &mut Report<E>| {
+            report.attach_deferred(location, info, context);
+        }));
+        self
+    }
+
+    /// Propagate the report converting the error using the given function.
+    #[track_caller]
+    pub fn propagate_map<F, M>(self, map: M) -> LightReport<F>
+    where
+        M: FnOnce(E) -> F,
+    {
+        let report = self.into_report(ReportContext::light());
+        let Report { error, context } = report.propagate_map(map);
+        let mut context = *context;
+        let entry = context.items.remove(context.error_item);
+        LightReport {
+            error,
+            items: context.items,
+            location: Location::caller(),
+            info: entry.info,
+            deferred: Vec::new(),
+        }
+    }
+
+    /// Upgrade to a full report, capturing the backtrace and span trace.
+    ///
+    /// Without the `std` feature, backtraces and span traces are not captured.
+    #[track_caller]
+    pub fn upgrade(self) -> Report<E> {
+        self.into_report(ReportContext::capture())
+    }
+
+    /// Build a report with the given context, recording the deferred context.
+    ///
+    /// Metrics are not recorded as the report has been created already.
+    fn into_report(self, mut context: ReportContext) -> Report<E> {
+        context.items = self.items;
+        context.error_item = context.items.len();
+        context.items.push(ContextEntry {
+            location: self.location.into(),
+            item: ReportItem::Error,
+            repeated: 0,
+            info: self.info,
+            error_type: None,
+        });
+        context.mark_error_layer::<E>();
+        let mut report = Report {
+            error: self.error,
+            context: Box::new(context),
+        };
+        for deferred in self.deferred {
+            deferred(&mut report);
+        }
+        report
+            .context
+            .enforce_max_items(Limits::current().max_items());
+        report
+    }
+}
+
+impl<E: Error> From<LightReport<E>> for Report<E> {
+    #[track_caller]
+    fn from(report: LightReport<E>) -> Self {
+        report.upgrade()
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for LightReport<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LightReport")
+            .field("error", &self.error)
+            .field("items", &self.items)
+            .field("location", &self.location)
+            .field("info", &self.info)
+            .field("deferred", &self.deferred.len())
+            .finish()
+    }
+}
+
+impl<E: Error> fmt::Display for LightReport<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match ErrorMessage::of(&self.error) {
+            Some(message) => message.fmt(f),
+            None => f.write_str("<unknown error>"),
+        }
+    }
+}
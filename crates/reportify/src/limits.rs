@@ -0,0 +1,306 @@
+//! Limits bounding the size of reports.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::Severity;
+use crate::render::Format;
+
+/// Globally configured maximum number of context items.
+static MAX_ITEMS: AtomicUsize = AtomicUsize::new(Limits::DEFAULT.max_items);
+
+/// Globally configured maximum rendered size.
+static MAX_RENDERED_LEN: AtomicUsize = AtomicUsize::new(Limits::DEFAULT.max_rendered_len);
+
+/// Limits bounding the size of reports.
+///
+/// Errors propagated through retry loops or recursive functions may accumulate a large
+/// number of context items. To keep reports readable, the number of context items
+/// retained by a report and the total size of the rendered report are bounded. When the
+/// number of context items exceeds the limit, items are evicted from the middle of the
+/// report, keeping the items closest to the origin of the error as well as the most
+/// recent ones. When the rendered report exceeds the size limit, it is truncated after
+/// the last context item or section that fits. In both cases, an explicit marker
+/// indicates that something has been omitted.
+///
+/// Limits are configured globally via [`Limits::install`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct Limits {
+    /// Maximum number of context items retained by a report.
+    max_items: usize,
+    /// Maximum size of the rendered report in bytes.
+    max_rendered_len: usize,
+}
+
+impl Limits {
+    /// Default limits.
+    pub const DEFAULT: Self = Self {
+        max_items: 128,
+        max_rendered_len: 64 * 1024,
+    };
+
+    /// Minimal number of context items retained by a report.
+    const MIN_ITEMS: usize = 3;
+
+    /// Limits that do not bound the size of reports.
+    pub const fn unbounded() -> Self {
+        Self {
+            max_items: usize::MAX,
+            max_rendered_len: usize::MAX,
+        }
+    }
+
+    /// Set the maximum number of context items retained by a report.
+    ///
+    /// At least three items are always retained.
+    pub const fn with_max_items(mut self, max_items: usize) -> Self {
+        self.max_items = if max_items < Self::MIN_ITEMS {
+            Self::MIN_ITEMS
+        } else {
+            max_items
+        };
+        self
+    }
+
+    /// Set the maximum size of the rendered report in bytes.
+    pub const fn with_max_rendered_len(mut self, max_rendered_len: usize) -> Self {
+        self.max_rendered_len = max_rendered_len;
+        self
+    }
+
+    /// Maximum number of context items retained by a report.
+    #[must_use]
+    pub const fn max_items(&self) -> usize {
+        self.max_items
+    }
+
+    /// Maximum size of the rendered report in bytes.
+    #[must_use]
+    pub const fn max_rendered_len(&self) -> usize {
+        self.max_rendered_len
+    }
+
+    /// Install the limits globally.
+    pub fn install(self) {
+        MAX_ITEMS.store(self.max_items, Ordering::Relaxed);
+        MAX_RENDERED_LEN.store(self.max_rendered_len, Ordering::Relaxed);
+    }
+
+    /// Globally installed limits.
+    pub fn current() -> Self {
+        Self {
+            max_items: MAX_ITEMS.load(Ordering::Relaxed),
+            max_rendered_len: MAX_RENDERED_LEN.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Format truncating the rendered report after a given number of bytes.
+///
+/// The report is truncated at the boundaries of its parts, e.g., context items and
+/// sections, such that the markup of the format stays intact. Once a part does not
+/// fit, all further parts are omitted, except for those closing already rendered parts,
+/// and a truncation marker is rendered in the format before finishing the report.
+pub(crate) struct TruncatingFormat<'f> {
+    /// Underlying format.
+    inner: &'f mut dyn Format,
+    /// Buffer for rendering a part before deciding whether it fits.
+    buffer: String,
+    /// Number of bytes that can still be written.
+    remaining: usize,
+    /// Number of bytes that have been omitted.
+    omitted: usize,
+    /// Whether the currently open parts, e.g., the context, have been rendered.
+    open: Vec<bool>,
+}
+
+impl<'f> TruncatingFormat<'f> {
+    /// Create a new format truncating the output after `max_len` bytes.
+    pub(crate) fn new(inner: &'f mut dyn Format, max_len: usize) -> Self {
+        Self {
+            inner,
+            buffer: String::new(),
+            remaining: max_len,
+            omitted: 0,
+            open: Vec::new(),
+        }
+    }
+
+    /// Render a part, unless it does not fit or a preceding part has been omitted.
+    ///
+    /// Returns whether the part has been rendered.
+    fn part(
+        &mut self,
+        f: &mut dyn Write,
+        render: impl FnOnce(&mut dyn Format, &mut dyn Write) -> fmt::Result,
+    ) -> Result<bool, fmt::Error> {
+        self.buffer.clear();
+        render(&mut *self.inner, &mut self.buffer)?;
+        if self.omitted == 0 && self.buffer.len() <= self.remaining {
+            self.remaining -= self.buffer.len();
+            f.write_str(&self.buffer)?;
+            Ok(true)
+        } else {
+            self.omitted += self.buffer.len();
+            Ok(false)
+        }
+    }
+
+    /// Render a part opening other parts, e.g., the context.
+    fn opening(
+        &mut self,
+        f: &mut dyn Write,
+        render: impl FnOnce(&mut dyn Format, &mut dyn Write) -> fmt::Result,
+    ) -> fmt::Result {
+        let rendered = self.part(f, render)?;
+        self.open.push(rendered);
+        Ok(())
+    }
+
+    /// Render a part closing the last opened part, if it has been rendered.
+    ///
+    /// Closing parts are rendered even if they do not fit.
+    fn closing(
+        &mut self,
+        f: &mut dyn Write,
+        render: impl FnOnce(&mut dyn Format, &mut dyn Write) -> fmt::Result,
+    ) -> fmt::Result {
+        self.buffer.clear();
+        render(&mut *self.inner, &mut self.buffer)?;
+        if self.open.pop().unwrap_or(false) {
+            self.remaining = self.remaining.saturating_sub(self.buffer.len());
+            f.write_str(&self.buffer)?;
+        }
+        Ok(())
+    }
+}
+
+impl Format for TruncatingFormat<'_> {
+    fn begin(&mut self, f: &mut dyn Write) -> fmt::Result {
+        self.opening(f, |format, f| format.begin(f))
+    }
+
+    fn message(&mut self, f: &mut dyn Write, severity: Severity, message: &str) -> fmt::Result {
+        self.part(f, |format, f| format.message(f, severity, message))?;
+        Ok(())
+    }
+
+    fn cause(&mut self, f: &mut dyn Write, cause: &str) -> fmt::Result {
+        self.part(f, |format, f| format.cause(f, cause))?;
+        Ok(())
+    }
+
+    fn begin_context(&mut self, f: &mut dyn Write) -> fmt::Result {
+        self.opening(f, |format, f| format.begin_context(f))
+    }
+
+    fn context_item(
+        &mut self,
+        f: &mut dyn Write,
+        location: &str,
+        message: &str,
+        repeated: usize,
+    ) -> fmt::Result {
+        self.part(f, |format, f| {
+            format.context_item(f, location, message, repeated)
+        })?;
+        Ok(())
+    }
+
+    fn omitted(&mut self, f: &mut dyn Write, count: usize) -> fmt::Result {
+        self.part(f, |format, f| format.omitted(f, count))?;
+        Ok(())
+    }
+
+    fn begin_layer(&mut self, f: &mut dyn Write, error_type: &str) -> fmt::Result {
+        self.opening(f, |format, f| format.begin_layer(f, error_type))
+    }
+
+    fn end_layer(&mut self, f: &mut dyn Write) -> fmt::Result {
+        self.closing(f, |format, f| format.end_layer(f))
+    }
+
+    fn end_context(&mut self, f: &mut dyn Write) -> fmt::Result {
+        self.closing(f, |format, f| format.end_context(f))
+    }
+
+    fn help(&mut self, f: &mut dyn Write, help: &[&str]) -> fmt::Result {
+        self.part(f, |format, f| format.help(f, help))?;
+        Ok(())
+    }
+
+    fn begin_nested(&mut self, f: &mut dyn Write, title: &str) -> fmt::Result {
+        self.opening(f, |format, f| format.begin_nested(f, title))
+    }
+
+    fn end_nested(&mut self, f: &mut dyn Write) -> fmt::Result {
+        self.closing(f, |format, f| format.end_nested(f))
+    }
+
+    fn section(&mut self, f: &mut dyn Write, title: &str, content: &str) -> fmt::Result {
+        self.part(f, |format, f| format.section(f, title, content))?;
+        Ok(())
+    }
+
+    fn truncated(&mut self, f: &mut dyn Write, omitted: usize) -> fmt::Result {
+        self.inner.truncated(f, omitted)
+    }
+
+    fn end(&mut self, f: &mut dyn Write) -> fmt::Result {
+        // Nested reports end before the enclosing report.
+        if self.open.len() == 1 && self.omitted > 0 {
+            self.inner.truncated(f, self.omitted)?;
+        }
+        self.closing(f, |format, f| format.end(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TruncatingFormat;
+    use crate::Severity;
+    use crate::render::{Format, Html, Text};
+
+    #[test]
+    fn test_truncating_format() {
+        let mut output = String::new();
+        let mut text = Text::new();
+        let mut format = TruncatingFormat::new(&mut text, 16);
+        format.begin(&mut output).expect("should not fail");
+        format
+            .message(&mut output, Severity::Error, "disk on fire")
+            .expect("should not fail");
+        format
+            .cause(&mut output, "no water")
+            .expect("should not fail");
+        format.end(&mut output).expect("should not fail");
+        assert_eq!(
+            output,
+            "disk on fire\n\n… report truncated (22 bytes omitted)\n"
+        );
+        let mut output = String::new();
+        let mut html = Html;
+        let mut format = TruncatingFormat::new(&mut html, 64);
+        format.begin(&mut output).expect("should not fail");
+        format.begin_context(&mut output).expect("should not fail");
+        format
+            .context_item(&mut output, "src/main.rs:1:1", "<writing output>", 0)
+            .expect("should not fail");
+        format.end_context(&mut output).expect("should not fail");
+        format.end(&mut output).expect("should not fail");
+        assert_eq!(
+            output,
+            "<div class=\"report\">\n<ul class=\"context\">\n</ul>\n\
+             <p class=\"truncated\">… report truncated (62 bytes omitted)</p>\n</div>\n"
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Metrics counting created and logged reports.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::Error;
+
+/// Globally configured switch for counting reports.
+static COUNTERS: AtomicBool = AtomicBool::new(Metrics::DEFAULT.counters);
+
+/// Globally configured switch for emitting metric events.
+static EVENTS: AtomicBool = AtomicBool::new(Metrics::DEFAULT.events);
+
+/// Number of created and logged reports by error type and code.
+static COUNTS: Mutex<BTreeMap<(&'static str, Option<String>), Counts>> =
+    Mutex::new(BTreeMap::new());
+
+/// Metrics counting created and logged reports.
+///
+/// When enabled, reports are counted by the name of their error type and their
+/// [error code][Error::code], if any, when they are created and when they are logged,
+/// e.g., via [`ResultExt::log_ok`][crate::ResultExt::log_ok]. The counts are available
+/// via [`stats`]. In addition, a tracing event with target `reportify::metrics` can be
+/// emitted for each count. The events follow the conventions of
+/// [`tracing-opentelemetry`](https://docs.rs/tracing-opentelemetry)'s `MetricsLayer`,
+/// incrementing the monotonic counters `reportify.reports.created` and
+/// `reportify.reports.logged` with the attributes `error.type` and `error.code`, such
+/// that they are exported as OTLP metrics.
+///
+/// Metrics are disabled by default and configured globally via [`Metrics::install`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct Metrics {
+    /// Count reports.
+    counters: bool,
+    /// Emit metric events.
+    events: bool,
+}
+
+impl Metrics {
+    /// Default configuration with metrics disabled.
+    pub const DEFAULT: Self = Self {
+        counters: false,
+        events: false,
+    };
+
+    /// Configuration with counting reports and emitting metric events enabled.
+    pub const fn enabled() -> Self {
+        Self {
+            counters: true,
+            events: true,
+        }
+    }
+
+    /// Enable or disable counting reports.
+    pub const fn with_counters(mut self, counters: bool) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// Enable or disable emitting metric events.
+    pub const fn with_events(mut self, events: bool) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Whether reports are counted.
+    #[must_use]
+    pub const fn counters(&self) -> bool {
+        self.counters
+    }
+
+    /// Whether metric events are emitted.
+    #[must_use]
+    pub const fn events(&self) -> bool {
+        self.events
+    }
+
+    /// Install the configuration globally.
+    pub fn install(self) {
+        COUNTERS.store(self.counters, Ordering::Relaxed);
+        EVENTS.store(self.events, Ordering::Relaxed);
+    }
+
+    /// Globally installed configuration.
+    pub fn current() -> Self {
+        Self {
+            counters: COUNTERS.load(Ordering::Relaxed),
+            events: EVENTS.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Number of created and logged reports.
+#[derive(Debug, Clone, Copy, Default)]
+struct Counts {
+    created: u64,
+    logged: u64,
+}
+
+/// Snapshot of the report counters.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    counters: Vec<Counter>,
+}
+
+impl Stats {
+    /// Iterator over the counters, ordered by error type and code.
+    pub fn iter(&self) -> impl Iterator<Item = &Counter> {
+        self.counters.iter()
+    }
+
+    /// Counter for the given error type name and code, if any reports have been counted.
+    #[must_use]
+    pub fn get(&self, error_type: &str, code: Option<&str>) -> Option<&Counter> {
+        self.counters
+            .iter()
+            .find(|counter| counter.error_type == error_type && counter.code() == code)
+    }
+
+    /// Total number of created reports.
+    #[must_use]
+    pub fn created(&self) -> u64 {
+        self.counters.iter().map(|counter| counter.created).sum()
+    }
+
+    /// Total number of logged reports.
+    #[must_use]
+    pub fn logged(&self) -> u64 {
+        self.counters.iter().map(|counter| counter.logged).sum()
+    }
+}
+
+/// Counter of reports with a specific error type and code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter {
+    error_type: &'static str,
+    code: Option<String>,
+    created: u64,
+    logged: u64,
+}
+
+impl Counter {
+    /// Name of the error type.
+    #[must_use]
+    pub fn error_type(&self) -> &'static str {
+        self.error_type
+    }
+
+    /// Error code, if any.
+    #[must_use]
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// Number of created reports.
+    #[must_use]
+    pub fn created(&self) -> u64 {
+        self.created
+    }
+
+    /// Number of logged reports.
+    #[must_use]
+    pub fn logged(&self) -> u64 {
+        self.logged
+    }
+}
+
+/// Take a snapshot of the report counters.
+pub fn stats() -> Stats {
+    let counts = COUNTS.lock().unwrap_or_else(PoisonError::into_inner);
+    Stats {
+        counters: counts
+            .iter()
+            .map(|((error_type, code), counts)| Counter {
+                error_type,
+                code: code.clone(),
+                created: counts.created,
+                logged: counts.logged,
+            })
+            .collect(),
+    }
+}
+
+/// Reset the report counters.
+pub fn reset() {
+    COUNTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// Event to be counted.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Event {
+    Created,
+    Logged,
+}
+
+/// Count the given event for a report with the given error.
+pub(crate) fn record<E: Error>(error: &E, event: Event) {
+    let metrics = Metrics::current();
+    if !metrics.counters && !metrics.events {
+        return;
+    }
+    let error_type = std::any::type_name::<E>();
+    let code = error.code();
+    if metrics.counters {
+        let mut counts = COUNTS.lock().unwrap_or_else(PoisonError::into_inner);
+        let counts = counts
+            .entry((error_type, code.map(ToOwned::to_owned)))
+            .or_default();
+        match event {
+            Event::Created => counts.created += 1,
+            Event::Logged => counts.logged += 1,
+        }
+    }
+    if metrics.events {
+        match event {
+            Event::Created => tracing::event!(
+                target: "reportify::metrics",
+                tracing::Level::INFO,
+                monotonic_counter.reportify.reports.created = 1u64,
+                error.r#type = error_type,
+                error.code = code,
+            ),
+            Event::Logged => tracing::event!(
+                target: "reportify::metrics",
+                tracing::Level::INFO,
+                monotonic_counter.reportify.reports.logged = 1u64,
+                error.r#type = error_type,
+                error.code = code,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Metrics, stats};
+    use crate::{Report, ResultExt};
+
+    #[derive(Debug)]
+    struct CountedError;
+
+    impl crate::Error for CountedError {
+        fn message(&self) -> Option<&dyn std::fmt::Display> {
+            Some(&"counted error")
+        }
+
+        fn code(&self) -> Option<&str> {
+            Some("E42")
+        }
+    }
+
+    /// Restore the previously installed configuration when dropped.
+    struct Restore(Metrics);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            self.0.install();
+        }
+    }
+
+    #[test]
+    fn test_counters() {
+        let _restore = Restore(Metrics::current());
+        Metrics::DEFAULT.with_counters(true).install();
+        let error_type = std::any::type_name::<CountedError>();
+        for _ in 0..3 {
+            let _ = Report::<CountedError>::from(CountedError);
+        }
+        Err::<(), _>(CountedError).log_ok();
+        let stats = stats();
+        let counter = stats
+            .get(error_type, Some("E42"))
+            .expect("counter should exist");
+        assert_eq!(counter.created(), 4);
+        assert_eq!(counter.logged(), 1);
+        assert!(stats.get(error_type, None).is_none());
+    }
+}
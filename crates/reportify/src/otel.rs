@@ -0,0 +1,118 @@
+//! Recording of reports as OpenTelemetry exceptions.
+//!
+//! Reports are recorded on spans following the [semantic conventions for
+//! exceptions](https://opentelemetry.io/docs/specs/semconv/exceptions/exceptions-spans/).
+//! The spans must be exported via [`tracing-opentelemetry`](https://docs.rs/tracing-opentelemetry),
+//! otherwise recording a report has no effect. Reports logged via
+//! [`ResultExt::log_ok`][crate::ResultExt::log_ok] or
+//! [`ResultExt::ignore`][crate::ResultExt::ignore] are recorded on the current span
+//! automatically.
+
+use opentelemetry::KeyValue;
+use opentelemetry::trace::Status;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::i18n::ErrorMessage;
+use crate::{Error, Report, Severity};
+
+impl<E: Error> Report<E> {
+    /// Record the report as an exception on the current span.
+    ///
+    /// See [`Report::record_exception_on`].
+    pub fn record_exception(&self) {
+        self.record_exception_on(&tracing::Span::current());
+    }
+
+    /// Record the report as an exception on the given span.
+    ///
+    /// Adds an `exception` event with the attributes `exception.type`,
+    /// `exception.message`, and `exception.stacktrace` to the span and, unless the
+    /// report is a [warning][Severity::Warning], sets the status of the span to error.
+    /// The rendered report, including the context and the backtrace, is used as the
+    /// stacktrace.
+    pub fn record_exception_on(&self, span: &tracing::Span) {
+        if span.is_disabled() {
+            return;
+        }
+        let error_type = std::any::type_name::<E>();
+        let message = ErrorMessage::of(&self.error)
+            .map_or_else(|| error_type.to_owned(), |message| message.to_string());
+        span.add_event(
+            "exception",
+            vec![
+                KeyValue::new("exception.type", error_type),
+                KeyValue::new("exception.message", message.clone()),
+                KeyValue::new("exception.stacktrace", self.to_string()),
+            ],
+        );
+        if self.severity() > Severity::Warning {
+            span.set_status(Status::error(message));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::Value;
+    use opentelemetry::trace::{Status, TracerProvider};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use crate::{Report, Reportify, ResultExt};
+
+    /// Spans exported while logging the given report as ignored.
+    fn export_ignored(report: impl FnOnce() -> Report<std::io::Error>) -> Vec<SpanData> {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("operation").entered();
+            Err::<(), _>(report()).ignore();
+        });
+        exporter.get_finished_spans().expect("should export spans")
+    }
+
+    #[test]
+    fn test_record_exception() {
+        let spans = export_ignored(|| {
+            Err::<(), _>(std::io::Error::other("disk on fire"))
+                .context("writing output")
+                .expect_err("should fail")
+        });
+        let span = spans.first().expect("should export span");
+        assert_eq!(span.status, Status::error("disk on fire"));
+        let event = span
+            .events
+            .iter()
+            .find(|event| event.name == "exception")
+            .expect("should record exception");
+        let attribute = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attribute| attribute.key.as_str() == key)
+                .map(|attribute| attribute.value.clone())
+        };
+        assert_eq!(
+            attribute("exception.type"),
+            Some(Value::from("std::io::error::Error"))
+        );
+        assert_eq!(
+            attribute("exception.message"),
+            Some(Value::from("disk on fire"))
+        );
+        let stacktrace = attribute("exception.stacktrace").expect("should record stacktrace");
+        assert!(stacktrace.as_str().contains(": writing output"));
+    }
+
+    #[test]
+    fn test_record_warning() {
+        let spans = export_ignored(|| std::io::Error::other("disk full").report().downgrade());
+        let span = spans.first().expect("should export span");
+        assert_eq!(span.status, Status::Unset);
+        assert!(span.events.iter().any(|event| event.name == "exception"));
+    }
+}
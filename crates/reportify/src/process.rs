@@ -0,0 +1,244 @@
+//! Execution of external processes with rich failure reports.
+//!
+//! The errors produced by [`Command`] do not include which command failed. The
+//! extension trait [`CommandExt`] adds methods for running commands that produce
+//! reports including the command line, working directory, exit status, and a bounded
+//! tail of the captured stderr output.
+
+use std::ffi::OsStr;
+use std::fmt::{self, Display};
+use std::io;
+use std::process::{Command, ExitStatus, Output};
+
+use crate::{Help, Report, ResultExt};
+
+/// Maximum number of lines of captured stderr output included in reports.
+const MAX_STDERR_LINES: usize = 20;
+
+/// Maximum number of bytes of captured stderr output included in reports.
+const MAX_STDERR_LEN: usize = 4096;
+
+/// Error running an external process.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CommandError {
+    /// Process could not be spawned.
+    Spawn(io::Error),
+    /// Process terminated unsuccessfully.
+    Failed(ExitStatus),
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Spawn(_) => f.write_str("unable to spawn process"),
+            CommandError::Failed(_) => f.write_str("process terminated unsuccessfully"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CommandError::Spawn(error) => Some(error),
+            CommandError::Failed(_) => None,
+        }
+    }
+}
+
+/// Extension trait for [`Command`] adding methods producing rich failure reports.
+pub trait CommandExt {
+    /// Run the command with inherited stdio and check that it terminates successfully.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the command cannot be spawned or terminates unsuccessfully.
+    fn run_checked(&mut self) -> Result<ExitStatus, Report<CommandError>>;
+
+    /// Run the command capturing its output and check that it terminates successfully.
+    ///
+    /// In case of failure, the tail of the captured stderr output is included in the
+    /// report.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the command cannot be spawned or terminates unsuccessfully.
+    fn output_checked(&mut self) -> Result<Output, Report<CommandError>>;
+}
+
+impl CommandExt for Command {
+    #[track_caller]
+    fn run_checked(&mut self) -> Result<ExitStatus, Report<CommandError>> {
+        let status = match self.status() {
+            Ok(status) => status,
+            Err(error) => return Err(spawn_failed(self, error)),
+        };
+        if status.success() {
+            Ok(status)
+        } else {
+            Err(CommandError::Failed(status))
+                .context(status.to_string())
+                .context(working_directory(self))
+                .context(format!("running `{}`", CommandLine(self)))
+        }
+    }
+
+    #[track_caller]
+    fn output_checked(&mut self) -> Result<Output, Report<CommandError>> {
+        let output = match self.output() {
+            Ok(output) => output,
+            Err(error) => return Err(spawn_failed(self, error)),
+        };
+        if output.status.success() {
+            Ok(output)
+        } else {
+            let mut report = Report::from(CommandError::Failed(output.status));
+            if let Some(tail) = stderr_tail(&output.stderr) {
+                report = report.with_context(tail);
+            }
+            Err(report)
+                .context(output.status.to_string())
+                .context(working_directory(self))
+                .context(format!("running `{}`", CommandLine(self)))
+        }
+    }
+}
+
+/// Create a report for a process that could not be spawned.
+#[track_caller]
+fn spawn_failed(command: &Command, error: io::Error) -> Report<CommandError> {
+    let program = command.get_program().to_string_lossy();
+    let help = match error.kind() {
+        io::ErrorKind::NotFound => Some(format!(
+            "check that `{program}` is installed and can be found via `PATH`"
+        )),
+        io::ErrorKind::PermissionDenied => Some(format!("check that `{program}` is executable")),
+        _ => None,
+    };
+    let mut report = Report::from(CommandError::Spawn(error))
+        .with_context(working_directory(command))
+        .with_context(format!("running `{}`", CommandLine(command)));
+    if let Some(help) = help {
+        report = report.with_context(Help::new(help));
+    }
+    report
+}
+
+/// Describe the working directory of the command.
+fn working_directory(command: &Command) -> String {
+    match command.get_current_dir() {
+        Some(dir) => format!("in working directory `{}`", dir.display()),
+        None => match std::env::current_dir() {
+            Ok(dir) => format!("in working directory `{}`", dir.display()),
+            Err(_) => "in unknown working directory".to_owned(),
+        },
+    }
+}
+
+/// Extract a bounded tail of the captured stderr output.
+fn stderr_tail(stderr: &[u8]) -> Option<String> {
+    let stderr = String::from_utf8_lossy(stderr);
+    let stderr = stderr.trim_end();
+    if stderr.is_empty() {
+        return None;
+    }
+    let mut start = stderr.len().saturating_sub(MAX_STDERR_LEN);
+    while !stderr.is_char_boundary(start) {
+        start += 1;
+    }
+    let tail = &stderr[start..];
+    let lines = tail.lines().collect::<Vec<_>>();
+    let omitted = start > 0 || lines.len() > MAX_STDERR_LINES;
+    let lines = &lines[lines.len().saturating_sub(MAX_STDERR_LINES)..];
+    let mut output = if omitted {
+        format!("stderr (last {} lines):", lines.len())
+    } else {
+        "stderr:".to_owned()
+    };
+    for line in lines {
+        output.push_str("\n  | ");
+        output.push_str(line);
+    }
+    Some(output)
+}
+
+/// Renders the command line of a command, quoting arguments if necessary.
+struct CommandLine<'c>(&'c Command);
+
+impl Display for CommandLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_quoted(f, self.0.get_program())?;
+        for arg in self.0.get_args() {
+            f.write_str(" ")?;
+            write_quoted(f, arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write the given string quoting it for a POSIX shell, if necessary.
+fn write_quoted(f: &mut fmt::Formatter<'_>, value: &OsStr) -> fmt::Result {
+    let value = value.to_string_lossy();
+    let is_plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=+,@%".contains(c));
+    if is_plain {
+        f.write_str(&value)
+    } else {
+        write!(f, "'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::{CommandError, CommandExt, CommandLine, stderr_tail};
+
+    #[test]
+    fn test_command_line() {
+        let mut command = Command::new("echo");
+        command.args(["hello world", "it's", "plain"]);
+        assert_eq!(
+            CommandLine(&command).to_string(),
+            r"echo 'hello world' 'it'\''s' plain"
+        );
+    }
+
+    #[test]
+    fn test_stderr_tail() {
+        let stderr = (0..30)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let tail = stderr_tail(stderr.as_bytes()).expect("should not be empty");
+        assert!(tail.starts_with("stderr (last 20 lines):\n  | line 10\n"));
+        assert!(tail.ends_with("\n  | line 29"));
+        assert_eq!(stderr_tail(b"\n"), None);
+    }
+
+    #[test]
+    fn test_spawn_failed() {
+        let report = Command::new("reportify-does-not-exist")
+            .arg("--help")
+            .run_checked()
+            .expect_err("should fail");
+        let rendered = report.to_string();
+        assert!(rendered.contains("running `reportify-does-not-exist --help`"));
+        assert!(rendered.contains("help: check that `reportify-does-not-exist` is installed"));
+    }
+
+    #[test]
+    fn test_output_failed() {
+        let report = Command::new("sh")
+            .args(["-c", "echo oops >&2; exit 3"])
+            .output_checked()
+            .expect_err("should fail");
+        assert!(matches!(report.error(), CommandError::Failed(status) if status.code() == Some(3)));
+        let rendered = report.to_string();
+        assert!(rendered.contains("exit status: 3"), "{rendered}");
+        assert!(rendered.contains(": stderr:\n  | oops\n"), "{rendered}");
+        assert!(rendered.contains("running `sh -c"), "{rendered}");
+    }
+}
@@ -0,0 +1,458 @@
+//! Transfer of reports between processes.
+//!
+//! A report can be converted into [`ReportData`], a serializable representation of its
+//! error message, causes, context items with their locations, and the textual
+//! representations of its backtrace and span trace. In the receiving process,
+//! [`RemoteReport`] reconstructs a report from this data, such that further context can
+//! be attached and a single report spanning both processes can be rendered.
+//!
+//! For the common case of a worker process reporting to a supervisor via stderr,
+//! [`Report::write_remote`] writes the report as a single line prefixed with
+//! [`MARKER`], which is picked up by [`RemoteReport::extract`] in the supervisor:
+//!
+//! ```ignore
+//! // In the worker process.
+//! if let Err(report) = run() {
+//!     report.write_remote(std::io::stderr())?;
+//!     std::process::exit(1);
+//! }
+//!
+//! // In the supervisor process.
+//! let output = Command::new("worker").output()?;
+//! if let Some(report) = RemoteReport::extract(&String::from_utf8_lossy(&output.stderr)) {
+//!     return Err(report.with_context("running worker"));
+//! }
+//! ```
+
+use std::backtrace::BacktraceStatus;
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use tracing_error::SpanTraceStatus;
+
+use crate::i18n::ErrorMessage;
+use crate::timeline::ItemInfo;
+use crate::{
+    ContextEntry, Error, Field, ItemLocation, Limits, Report, ReportContext, ReportItem, Severity,
+};
+
+/// Prefix of lines containing serialized reports.
+pub const MARKER: &str = "reportify-report: ";
+
+/// Serializable representation of a report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportData {
+    /// Name of the error type.
+    error_type: String,
+    /// Error message, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    /// Severity of the report.
+    #[serde(default, skip_serializing_if = "is_default")]
+    severity: Severity,
+    /// Messages of the chain of underlying errors.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    causes: Vec<String>,
+    /// Context items in the order they have been attached.
+    #[serde(default)]
+    items: Vec<ItemData>,
+    /// Traces of the report.
+    #[serde(flatten)]
+    traces: RemoteTraces,
+    /// Traces of reports previously reconstructed from other processes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    remote: Vec<RemoteTraces>,
+}
+
+impl ReportData {
+    /// Name of the error type.
+    #[must_use]
+    pub fn error_type(&self) -> &str {
+        &self.error_type
+    }
+
+    /// Error message, if any.
+    #[must_use]
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Serialize the data as JSON.
+    #[must_use]
+    #[expect(clippy::missing_panics_doc, reason = "serialization cannot fail")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("report data should be serializable")
+    }
+
+    /// Deserialize the data from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the JSON is not a serialized report.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Serializable representation of a context item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ItemData {
+    file: String,
+    line: u32,
+    column: u32,
+    #[serde(flatten)]
+    kind: ItemKind,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    repeated: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    info: Option<Box<ItemInfo>>,
+    /// Name of the error type, if the item is the item of an error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error_type: Option<String>,
+}
+
+/// Kind of a context item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ItemKind {
+    Message {
+        message: String,
+    },
+    Help {
+        message: String,
+    },
+    Fields {
+        fields: Vec<Field>,
+    },
+    Omitted {
+        count: usize,
+    },
+    /// Item of an error without message, retained to mark the start of its layer.
+    Discarded,
+}
+
+#[expect(
+    clippy::trivially_copy_pass_by_ref,
+    reason = "signature required by serde"
+)]
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// Textual representations of the traces of a report from another process.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct RemoteTraces {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) backtrace: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) span_trace: Option<String>,
+}
+
+impl<E: Error> Report<E> {
+    /// Convert the report into its serializable representation.
+    pub fn to_data(&self) -> ReportData {
+        let mut causes = Vec::new();
+        if let Some(error) = self.error.as_std_error() {
+            let mut source = error.source();
+            while let Some(error) = source {
+                causes.push(error.to_string());
+                source = error.source();
+            }
+        }
+        let items = self
+            .context
+            .items
+            .iter()
+            .filter_map(|entry| {
+                let kind = match &entry.item {
+                    ReportItem::Message(message) => ItemKind::Message {
+                        message: message.clone(),
+                    },
+                    ReportItem::Localized(message) => ItemKind::Message {
+                        message: message.to_string(),
+                    },
+                    ReportItem::Error => match ErrorMessage::of(&self.error) {
+                        Some(message) => ItemKind::Message {
+                            message: message.to_string(),
+                        },
+                        None => ItemKind::Discarded,
+                    },
+                    ReportItem::Assertion(assertion) => ItemKind::Message {
+                        message: assertion.to_string(),
+                    },
+                    ReportItem::Fields(fields) => ItemKind::Fields {
+                        fields: fields.iter().cloned().collect(),
+                    },
+                    ReportItem::Help(help) => ItemKind::Help {
+                        message: help.clone(),
+                    },
+                    ReportItem::Omitted(count) => ItemKind::Omitted { count: *count },
+                    ReportItem::Discarded => ItemKind::Discarded,
+                };
+                if matches!(kind, ItemKind::Discarded) && entry.error_type.is_none() {
+                    return None;
+                }
+                Some(ItemData {
+                    file: entry.location.file().to_owned(),
+                    line: entry.location.line(),
+                    column: entry.location.column(),
+                    kind,
+                    repeated: entry.repeated,
+                    info: entry.info.clone(),
+                    error_type: entry.error_type.as_deref().map(str::to_owned),
+                })
+            })
+            .collect();
+        let backtrace = &self.context.backtrace;
+        let span_trace = &self.context.span_trace;
+        ReportData {
+            error_type: std::any::type_name::<E>().to_owned(),
+            message: ErrorMessage::of(&self.error).map(|message| message.to_string()),
+            severity: self.severity(),
+            causes,
+            items,
+            traces: RemoteTraces {
+                backtrace: (backtrace.status() == BacktraceStatus::Captured)
+                    .then(|| backtrace.to_string()),
+                span_trace: (span_trace.status() == SpanTraceStatus::CAPTURED)
+                    .then(|| span_trace.to_string()),
+            },
+            remote: self.context.remote.clone(),
+        }
+    }
+
+    /// Serialize the report as JSON.
+    ///
+    /// Use [`RemoteReport::from_json`] to reconstruct the report.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        self.to_data().to_json()
+    }
+
+    /// Write the report as a single line prefixed with [`MARKER`].
+    ///
+    /// Use [`RemoteReport::extract`] to reconstruct the report from the output.
+    ///
+    /// # Errors
+    ///
+    /// Fails if writing fails.
+    pub fn write_remote<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{MARKER}{}", self.to_json())
+    }
+}
+
+/// Error of a report reconstructed from another process.
+///
+/// The reconstructed report contains the context items of the original report with
+/// their original locations and error types, including the item of the original error.
+/// Only if the serialized report does not identify the item of its error, the location
+/// at which the report has been reconstructed is used for it. The backtrace and span
+/// trace of the original report are rendered after the ones captured during the
+/// reconstruction.
+#[derive(Debug)]
+pub struct RemoteReport {
+    error_type: String,
+    message: Option<String>,
+    source: Option<Box<RemoteCause>>,
+}
+
+impl RemoteReport {
+    /// Reconstruct a report from its serializable representation.
+    #[track_caller]
+    #[must_use]
+    pub fn from_data(data: ReportData) -> Report<Self> {
+        let source = data.causes.into_iter().rev().fold(None, |source, message| {
+            Some(Box::new(RemoteCause { message, source }))
+        });
+        let error = RemoteReport {
+            error_type: data.error_type,
+            message: data.message,
+            source,
+        };
+        let mut context = ReportContext::capture();
+        context.severity = (data.severity != Severity::default()).then_some(data.severity);
+        let mut items = data
+            .items
+            .into_iter()
+            .map(|item| ContextEntry {
+                location: ItemLocation::Remote {
+                    file: item.file.into(),
+                    line: item.line,
+                    column: item.column,
+                },
+                item: match item.kind {
+                    ItemKind::Message { message } => ReportItem::Message(message),
+                    ItemKind::Fields { fields } => ReportItem::Fields(fields.into_iter().collect()),
+                    ItemKind::Help { message } => ReportItem::Help(message),
+                    ItemKind::Omitted { count } => ReportItem::Omitted(count),
+                    ItemKind::Discarded => ReportItem::Discarded,
+                },
+                repeated: item.repeated,
+                info: item.info,
+                error_type: item.error_type.map(Cow::Owned),
+            })
+            .collect::<Vec<_>>();
+        // The item of the original error becomes the item of the reconstructed error,
+        // such that the message is not rendered twice.
+        let mut error_type = None;
+        if let Some(index) = items.iter().rposition(|entry| entry.error_type.is_some()) {
+            items[index].item = ReportItem::Error;
+            error_type = items[index].error_type.take();
+            context.items = items;
+            context.error_item = index;
+        } else {
+            context.items.splice(0..0, items);
+            context.error_item = context.items.len() - 1;
+        }
+        context.remote.push(data.traces);
+        context.remote.extend(data.remote);
+        let mut report = Report::new(error, context);
+        // Keep the type of the original error as the layer of the reconstructed error.
+        if let Some(error_type) = error_type {
+            let index = report.context.error_item;
+            report.context.items[index].error_type = Some(error_type);
+        }
+        report
+            .context
+            .enforce_max_items(Limits::current().max_items());
+        report
+    }
+
+    /// Reconstruct a report from its JSON serialization.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the JSON is not a serialized report.
+    #[track_caller]
+    pub fn from_json(json: &str) -> Result<Report<Self>, serde_json::Error> {
+        Ok(Self::from_data(ReportData::from_json(json)?))
+    }
+
+    /// Reconstruct the last report written with [`Report::write_remote`] to the given
+    /// output, if any.
+    #[must_use]
+    #[track_caller]
+    pub fn extract(output: &str) -> Option<Report<Self>> {
+        let data = output
+            .lines()
+            .rev()
+            .filter_map(|line| line.trim_end().strip_prefix(MARKER))
+            .find_map(|json| ReportData::from_json(json).ok())?;
+        Some(Self::from_data(data))
+    }
+
+    /// Name of the error type of the original report.
+    #[must_use]
+    pub fn error_type(&self) -> &str {
+        &self.error_type
+    }
+}
+
+impl Display for RemoteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            Some(message) => f.write_str(message),
+            None => write!(f, "error of type `{}`", self.error_type),
+        }
+    }
+}
+
+impl StdError for RemoteReport {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_deref().map(|source| source as &dyn StdError)
+    }
+}
+
+/// Underlying error of a report reconstructed from another process.
+#[derive(Debug)]
+struct RemoteCause {
+    message: String,
+    source: Option<Box<RemoteCause>>,
+}
+
+impl Display for RemoteCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for RemoteCause {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_deref().map(|source| source as &dyn StdError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::RemoteReport;
+    use crate::{Help, ResultExt};
+
+    #[test]
+    fn test_round_trip() {
+        let report = Err::<(), _>(io::Error::other("disk on fire"))
+            .context("writing output")
+            .context(Help::new("extinguish the disk"))
+            .context(crate::fields!(attempt = 2))
+            .expect_err("should fail");
+        let json = report.to_json();
+        assert!(json.contains(r#""fields":[{"name":"attempt","value":"2"}]"#));
+        let mut output = b"some unrelated output\n".to_vec();
+        report.write_remote(&mut output).expect("should write");
+        let output = String::from_utf8(output).expect("should be UTF-8");
+        let remote = RemoteReport::extract(&output).expect("should contain report");
+        assert_eq!(remote.error().error_type(), "std::io::error::Error");
+        let remote = remote.with_context("running worker");
+        let rendered = remote.to_string();
+        assert!(rendered.starts_with("disk on fire\n"));
+        let remote_item = rendered
+            .find("src/remote.rs:")
+            .expect("should contain remote location");
+        assert!(rendered[remote_item..].contains(": writing output\n"));
+        assert!(rendered.contains(": running worker\n"));
+        let reconstructed = rendered.lines().nth(3).expect("should contain error item");
+        assert!(reconstructed.contains("src/remote.rs:"));
+        assert_eq!(
+            rendered.matches(": disk on fire\n").count(),
+            1,
+            "{rendered}"
+        );
+        assert!(rendered.contains("help: extinguish the disk"));
+        assert!(rendered.contains(": attempt=2\n"));
+        assert!(RemoteReport::extract("no report").is_none());
+    }
+
+    #[test]
+    fn test_round_trip_layers() {
+        crate::new_whatever_type!(WorkerError);
+        let report = Err::<(), _>(io::Error::other("disk on fire"))
+            .context("writing output")
+            .whatever::<WorkerError>()
+            .context("running job")
+            .expect_err("should fail");
+        let remote = RemoteReport::from_data(report.to_data());
+        let rendered = remote.render_layered();
+        let layers = rendered
+            .lines()
+            .filter(|line| line.ends_with(':') && !line.starts_with(' '))
+            .take_while(|line| !line.ends_with("Backtrace:"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            layers,
+            [
+                "reportify::remote::tests::test_round_trip_layers::WorkerError:",
+                "std::io::error::Error:",
+            ],
+            "{rendered}"
+        );
+        assert!(!rendered.contains("RemoteReport"), "{rendered}");
+    }
+}
@@ -0,0 +1,608 @@
+//! Output formats for rendering reports.
+//!
+//! A report is rendered by passing its parts, i.e., the error message, the causes, the
+//! context items, the suggestions, and the traces, to a [`Format`]. Besides [`Text`],
+//! which is used by the [`Display`][core::fmt::Display] implementation of reports,
+//! reports can be rendered as [`Markdown`], e.g., for support tickets, and as [`Html`],
+//! e.g., for web dashboards. Both escape the rendered messages and put traces into
+//! collapsible sections.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt::{self, Write};
+
+use crate::Severity;
+
+/// Output format for rendering reports.
+///
+/// The methods are called in the order in which they are declared. Messages passed to
+/// the methods are not escaped, hence, they must be escaped by the format, if necessary.
+#[expect(
+    clippy::missing_errors_doc,
+    reason = "the methods only fail if writing fails"
+)]
+pub trait Format {
+    /// Start rendering a report.
+    fn begin(&mut self, f: &mut dyn Write) -> fmt::Result {
+        let _ = f;
+        Ok(())
+    }
+
+    /// Render the error message of a report with the given severity.
+    fn message(&mut self, f: &mut dyn Write, severity: Severity, message: &str) -> fmt::Result;
+
+    /// Render an underlying cause of the error.
+    fn cause(&mut self, f: &mut dyn Write, cause: &str) -> fmt::Result;
+
+    /// Start rendering the context items.
+    fn begin_context(&mut self, f: &mut dyn Write) -> fmt::Result;
+
+    /// Render a context item attached at the given location.
+    ///
+    /// The message may span multiple lines.
+    fn context_item(
+        &mut self,
+        f: &mut dyn Write,
+        location: &str,
+        message: &str,
+        repeated: usize,
+    ) -> fmt::Result;
+
+    /// Render a marker for the given number of omitted context items.
+    fn omitted(&mut self, f: &mut dyn Write, count: usize) -> fmt::Result;
+
+    /// Start rendering the context items of an error layer with the given error type.
+    ///
+    /// Only called when rendering reports with
+    /// [`Report::render_layered_with`][crate::Report::render_layered_with].
+    fn begin_layer(&mut self, f: &mut dyn Write, error_type: &str) -> fmt::Result {
+        let _ = (f, error_type);
+        Ok(())
+    }
+
+    /// Finish rendering the context items of an error layer.
+    fn end_layer(&mut self, f: &mut dyn Write) -> fmt::Result {
+        let _ = f;
+        Ok(())
+    }
+
+    /// Finish rendering the context items.
+    fn end_context(&mut self, f: &mut dyn Write) -> fmt::Result {
+        let _ = f;
+        Ok(())
+    }
+
+    /// Render the suggestions on how to fix the cause of the error.
+    fn help(&mut self, f: &mut dyn Write, help: &[&str]) -> fmt::Result;
+
+    /// Start rendering a report nested in the report with the given title.
+    ///
+    /// The nested report is rendered with this format before [`Format::end_nested`] is
+    /// called. See [`Error::nested_reports`][crate::Error::nested_reports].
+    fn begin_nested(&mut self, f: &mut dyn Write, title: &str) -> fmt::Result {
+        write!(f, "\n{title}:\n")
+    }
+
+    /// Finish rendering a nested report.
+    fn end_nested(&mut self, f: &mut dyn Write) -> fmt::Result {
+        let _ = f;
+        Ok(())
+    }
+
+    /// Render a section with the given title, e.g., a backtrace.
+    fn section(&mut self, f: &mut dyn Write, title: &str, content: &str) -> fmt::Result;
+
+    /// Render a marker for the given number of bytes omitted due to the size limit.
+    ///
+    /// Only called if the report has been truncated according to the configured
+    /// [`Limits`][crate::Limits]. Parts that have been started before, e.g., the
+    /// context, have been finished already.
+    fn truncated(&mut self, f: &mut dyn Write, omitted: usize) -> fmt::Result {
+        writeln!(f, "\n… report truncated ({omitted} bytes omitted)")
+    }
+
+    /// Finish rendering a report.
+    fn end(&mut self, f: &mut dyn Write) -> fmt::Result {
+        let _ = f;
+        Ok(())
+    }
+}
+
+/// Plain text format.
+///
+/// Optionally, the error message is colored according to the severity of the report
+/// using ANSI escape codes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Text {
+    colors: bool,
+    /// Whether context items are rendered as part of an error layer.
+    in_layer: bool,
+}
+
+impl Text {
+    /// Create a plain text format without colors.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            colors: false,
+            in_layer: false,
+        }
+    }
+
+    /// Enable or disable colors.
+    ///
+    /// Without the `std` feature, colors are not supported.
+    #[must_use]
+    pub const fn with_colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+}
+
+impl Format for Text {
+    fn message(&mut self, f: &mut dyn Write, severity: Severity, message: &str) -> fmt::Result {
+        let message = match severity {
+            Severity::Error => String::from(message),
+            _ => format!("{severity}: {message}"),
+        };
+        #[cfg(feature = "std")]
+        if self.colors {
+            let style = match severity {
+                Severity::Warning => console::Style::new().yellow(),
+                Severity::Error => console::Style::new().red(),
+                Severity::Fatal => console::Style::new().red().bold(),
+            };
+            return writeln!(f, "{}", style.force_styling(true).apply_to(message));
+        }
+        writeln!(f, "{message}")
+    }
+
+    fn cause(&mut self, f: &mut dyn Write, cause: &str) -> fmt::Result {
+        writeln!(f, "  Caused by: {cause}")
+    }
+
+    fn begin_context(&mut self, f: &mut dyn Write) -> fmt::Result {
+        writeln!(f)
+    }
+
+    fn context_item(
+        &mut self,
+        f: &mut dyn Write,
+        location: &str,
+        message: &str,
+        repeated: usize,
+    ) -> fmt::Result {
+        if self.in_layer {
+            f.write_str("  ")?;
+        }
+        write!(f, "{location}: {message}")?;
+        if repeated > 0 {
+            write!(f, " (… repeated {} times)", repeated + 1)?;
+        }
+        writeln!(f)
+    }
+
+    fn omitted(&mut self, f: &mut dyn Write, count: usize) -> fmt::Result {
+        if self.in_layer {
+            f.write_str("  ")?;
+        }
+        writeln!(f, "… {count} {} omitted", context_items(count))
+    }
+
+    fn begin_layer(&mut self, f: &mut dyn Write, error_type: &str) -> fmt::Result {
+        self.in_layer = true;
+        writeln!(f, "{error_type}:")
+    }
+
+    fn end_layer(&mut self, f: &mut dyn Write) -> fmt::Result {
+        let _ = f;
+        self.in_layer = false;
+        Ok(())
+    }
+
+    fn help(&mut self, f: &mut dyn Write, help: &[&str]) -> fmt::Result {
+        writeln!(f)?;
+        for help in help {
+            writeln!(f, "help: {help}")?;
+        }
+        Ok(())
+    }
+
+    fn section(&mut self, f: &mut dyn Write, title: &str, content: &str) -> fmt::Result {
+        write!(f, "\n{title}:\n{content}")?;
+        if !content.ends_with('\n') {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Markdown format.
+///
+/// Sections are rendered as collapsible `<details>` elements, which are supported by
+/// most Markdown renderers, e.g., GitHub and GitLab. Error layers are rendered as
+/// nested lists.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Markdown {
+    /// Whether context items are rendered as part of an error layer.
+    in_layer: bool,
+}
+
+impl Markdown {
+    /// Create a Markdown format.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { in_layer: false }
+    }
+
+    /// Indentation of context items.
+    fn indent(self) -> &'static str {
+        if self.in_layer { "  " } else { "" }
+    }
+}
+
+impl Format for Markdown {
+    fn message(&mut self, f: &mut dyn Write, severity: Severity, message: &str) -> fmt::Result {
+        match severity {
+            Severity::Error => writeln!(f, "**{}**", MarkdownEscaped(message)),
+            _ => writeln!(f, "**{severity}: {}**", MarkdownEscaped(message)),
+        }
+    }
+
+    fn cause(&mut self, f: &mut dyn Write, cause: &str) -> fmt::Result {
+        writeln!(f, "\nCaused by: {}", MarkdownEscaped(cause))
+    }
+
+    fn begin_context(&mut self, f: &mut dyn Write) -> fmt::Result {
+        writeln!(f)
+    }
+
+    fn context_item(
+        &mut self,
+        f: &mut dyn Write,
+        location: &str,
+        message: &str,
+        repeated: usize,
+    ) -> fmt::Result {
+        let indent = self.indent();
+        write!(f, "{indent}- {}:", CodeSpan(location))?;
+        match message.split_once('\n') {
+            None => write!(f, " {}", MarkdownEscaped(message))?,
+            Some(("", _)) => {}
+            Some((first, _)) => write!(f, " {}", MarkdownEscaped(first))?,
+        }
+        if repeated > 0 {
+            write!(f, " (… repeated {} times)", repeated + 1)?;
+        }
+        writeln!(f)?;
+        if let Some((_, rest)) = message.split_once('\n') {
+            let block = code_block(rest);
+            writeln!(f)?;
+            for line in block.lines() {
+                writeln!(f, "{indent}  {line}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn omitted(&mut self, f: &mut dyn Write, count: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{}- *… {count} {} omitted*",
+            self.indent(),
+            context_items(count)
+        )
+    }
+
+    fn begin_layer(&mut self, f: &mut dyn Write, error_type: &str) -> fmt::Result {
+        self.in_layer = true;
+        writeln!(f, "- {}", CodeSpan(error_type))
+    }
+
+    fn end_layer(&mut self, f: &mut dyn Write) -> fmt::Result {
+        let _ = f;
+        self.in_layer = false;
+        Ok(())
+    }
+
+    fn help(&mut self, f: &mut dyn Write, help: &[&str]) -> fmt::Result {
+        writeln!(f)?;
+        for help in help {
+            writeln!(f, "- **help:** {}", MarkdownEscaped(help))?;
+        }
+        Ok(())
+    }
+
+    fn begin_nested(&mut self, f: &mut dyn Write, title: &str) -> fmt::Result {
+        writeln!(
+            f,
+            "\n<details>\n<summary>{}</summary>\n",
+            HtmlEscaped(title)
+        )
+    }
+
+    fn end_nested(&mut self, f: &mut dyn Write) -> fmt::Result {
+        writeln!(f, "</details>")
+    }
+
+    fn section(&mut self, f: &mut dyn Write, title: &str, content: &str) -> fmt::Result {
+        writeln!(
+            f,
+            "\n<details>\n<summary>{}</summary>\n\n{}\n</details>",
+            HtmlEscaped(title),
+            code_block(content)
+        )
+    }
+
+    fn truncated(&mut self, f: &mut dyn Write, omitted: usize) -> fmt::Result {
+        writeln!(f, "\n*… report truncated ({omitted} bytes omitted)*")
+    }
+}
+
+/// HTML format.
+///
+/// The report is rendered as a `<div>` with the class `report`. Sections are rendered
+/// as collapsible `<details>` elements.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Html;
+
+impl Format for Html {
+    fn begin(&mut self, f: &mut dyn Write) -> fmt::Result {
+        writeln!(f, "<div class=\"report\">")
+    }
+
+    fn message(&mut self, f: &mut dyn Write, severity: Severity, message: &str) -> fmt::Result {
+        match severity {
+            Severity::Error => writeln!(
+                f,
+                "<p class=\"message\"><strong>{}</strong></p>",
+                HtmlEscaped(message)
+            ),
+            _ => writeln!(
+                f,
+                "<p class=\"message {severity}\"><strong>{severity}: {}</strong></p>",
+                HtmlEscaped(message)
+            ),
+        }
+    }
+
+    fn cause(&mut self, f: &mut dyn Write, cause: &str) -> fmt::Result {
+        writeln!(
+            f,
+            "<p class=\"cause\">Caused by: {}</p>",
+            HtmlEscaped(cause)
+        )
+    }
+
+    fn begin_context(&mut self, f: &mut dyn Write) -> fmt::Result {
+        writeln!(f, "<ul class=\"context\">")
+    }
+
+    fn context_item(
+        &mut self,
+        f: &mut dyn Write,
+        location: &str,
+        message: &str,
+        repeated: usize,
+    ) -> fmt::Result {
+        write!(f, "<li><code>{}</code>: ", HtmlEscaped(location))?;
+        if message.contains('\n') {
+            write!(f, "<pre>{}</pre>", HtmlEscaped(message))?;
+        } else {
+            write!(f, "{}", HtmlEscaped(message))?;
+        }
+        if repeated > 0 {
+            write!(
+                f,
+                " <span class=\"repeated\">(… repeated {} times)</span>",
+                repeated + 1
+            )?;
+        }
+        writeln!(f, "</li>")
+    }
+
+    fn omitted(&mut self, f: &mut dyn Write, count: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "<li class=\"omitted\">… {count} {} omitted</li>",
+            context_items(count)
+        )
+    }
+
+    fn begin_layer(&mut self, f: &mut dyn Write, error_type: &str) -> fmt::Result {
+        writeln!(
+            f,
+            "<li class=\"layer\"><code>{}</code>\n<ul>",
+            HtmlEscaped(error_type)
+        )
+    }
+
+    fn end_layer(&mut self, f: &mut dyn Write) -> fmt::Result {
+        writeln!(f, "</ul>\n</li>")
+    }
+
+    fn end_context(&mut self, f: &mut dyn Write) -> fmt::Result {
+        writeln!(f, "</ul>")
+    }
+
+    fn help(&mut self, f: &mut dyn Write, help: &[&str]) -> fmt::Result {
+        writeln!(f, "<ul class=\"help\">")?;
+        for help in help {
+            writeln!(f, "<li><strong>help:</strong> {}</li>", HtmlEscaped(help))?;
+        }
+        writeln!(f, "</ul>")
+    }
+
+    fn begin_nested(&mut self, f: &mut dyn Write, title: &str) -> fmt::Result {
+        writeln!(f, "<details><summary>{}</summary>", HtmlEscaped(title))
+    }
+
+    fn end_nested(&mut self, f: &mut dyn Write) -> fmt::Result {
+        writeln!(f, "</details>")
+    }
+
+    fn section(&mut self, f: &mut dyn Write, title: &str, content: &str) -> fmt::Result {
+        writeln!(
+            f,
+            "<details><summary>{}</summary><pre>{}</pre></details>",
+            HtmlEscaped(title),
+            HtmlEscaped(content.trim_end())
+        )
+    }
+
+    fn truncated(&mut self, f: &mut dyn Write, omitted: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "<p class=\"truncated\">… report truncated ({omitted} bytes omitted)</p>"
+        )
+    }
+
+    fn end(&mut self, f: &mut dyn Write) -> fmt::Result {
+        writeln!(f, "</div>")
+    }
+}
+
+/// Report that can be nested in another report.
+///
+/// Implemented by [`Report`][crate::Report], see
+/// [`Error::nested_reports`][crate::Error::nested_reports].
+pub trait NestedReport {
+    /// Render the report in the given format.
+    #[expect(
+        clippy::missing_errors_doc,
+        reason = "the method only fails if writing fails"
+    )]
+    fn render_nested(&self, f: &mut dyn Write, format: &mut dyn Format) -> fmt::Result;
+}
+
+/// Noun for the given number of context items.
+fn context_items(count: usize) -> &'static str {
+    if count == 1 {
+        "context item"
+    } else {
+        "context items"
+    }
+}
+
+/// Fenced Markdown code block with a fence that is not contained in the content.
+pub(crate) fn code_block(content: &str) -> String {
+    let fence = "`".repeat(longest_backtick_run(content).max(2) + 1);
+    format!("{fence}text\n{}\n{fence}", content.trim_end())
+}
+
+/// Length of the longest run of backticks in the given string.
+fn longest_backtick_run(value: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in value.chars() {
+        if c == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+
+/// Markdown code span.
+struct CodeSpan<'s>(&'s str);
+
+impl fmt::Display for CodeSpan<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fence = "`".repeat(longest_backtick_run(self.0) + 1);
+        if self.0.starts_with('`') || self.0.ends_with('`') {
+            write!(f, "{fence} {} {fence}", self.0)
+        } else {
+            write!(f, "{fence}{}{fence}", self.0)
+        }
+    }
+}
+
+/// Text escaped for inline Markdown.
+struct MarkdownEscaped<'s>(&'s str);
+
+impl fmt::Display for MarkdownEscaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~' | '&' | '!' => {
+                    write!(f, "\\{c}")?;
+                }
+                '\n' => f.write_char(' ')?,
+                _ => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Text escaped for HTML.
+struct HtmlEscaped<'s>(&'s str);
+
+impl fmt::Display for HtmlEscaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&#39;")?,
+                _ => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CodeSpan, Format, HtmlEscaped, Markdown, MarkdownEscaped, Text, code_block};
+    use crate::{Help, Report, ResultExt};
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(
+            HtmlEscaped("<script>alert('&')</script>").to_string(),
+            "&lt;script&gt;alert(&#39;&amp;&#39;)&lt;/script&gt;"
+        );
+        assert_eq!(
+            MarkdownEscaped("**bold** <b>[link](x)").to_string(),
+            r"\*\*bold\*\* \<b\>\[link\](x)"
+        );
+        assert_eq!(CodeSpan("src/main.rs:1:1").to_string(), "`src/main.rs:1:1`");
+        assert_eq!(CodeSpan("a`b").to_string(), "``a`b``");
+        assert_eq!(code_block("```\n"), "````text\n```\n````");
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let report: Report<std::io::Error> = Err::<(), _>(std::io::Error::other("<disk> on fire"))
+            .context("writing `output`")
+            .context(Help::new("extinguish *the* disk"))
+            .expect_err("should fail");
+        let markdown = report.render_markdown();
+        assert!(markdown.starts_with("**\\<disk\\> on fire**\n"));
+        assert!(markdown.contains(".rs:"));
+        assert!(markdown.contains("`: writing \\`output\\`\n"));
+        assert!(markdown.contains("- **help:** extinguish \\*the\\* disk\n"));
+        let html = report.render_html();
+        assert!(html.starts_with("<div class=\"report\">\n<p class=\"message\"><strong>&lt;disk&gt; on fire</strong></p>\n"));
+        assert!(html.contains("</code>: writing `output`</li>\n"));
+        assert!(html.ends_with("</div>\n"));
+    }
+
+    #[test]
+    fn test_omitted() {
+        let mut output = String::new();
+        Text::new().omitted(&mut output, 1).expect("should render");
+        Markdown::new()
+            .omitted(&mut output, 2)
+            .expect("should render");
+        assert_eq!(
+            output,
+            "… 1 context item omitted\n- *… 2 context items omitted*\n"
+        );
+    }
+}
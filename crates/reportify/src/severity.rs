@@ -0,0 +1,109 @@
+//! Severity of reports.
+
+use core::fmt::{self, Display};
+
+/// Severity of a report.
+///
+/// The severity of a report defaults to the [severity of its
+/// error][crate::Error::severity] and can be adjusted with
+/// [`Report::downgrade`][crate::Report::downgrade] and
+/// [`Report::escalate`][crate::Report::escalate]. It controls the prefix of the rendered
+/// report, the tracing level used when logging the report, e.g., via
+/// [`ResultExt::log_ok`][crate::ResultExt::log_ok], and the exit code of
+#[cfg_attr(feature = "std", doc = "[`Exit`][crate::Exit].")]
+#[cfg_attr(not(feature = "std"), doc = "`Exit`.")]
+/// Reports with the default severity, [`Severity::Error`], are rendered without prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Severity {
+    /// Degraded operation, the program continues.
+    Warning,
+    /// Failed operation.
+    #[default]
+    Error,
+    /// Failed operation, the program cannot continue.
+    Fatal,
+}
+
+impl Severity {
+    /// Label of the severity, e.g., `warning`.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Fatal => "fatal",
+        }
+    }
+
+    /// Next lower severity, if any, otherwise the severity itself.
+    #[must_use]
+    pub const fn downgrade(self) -> Self {
+        match self {
+            Severity::Warning | Severity::Error => Severity::Warning,
+            Severity::Fatal => Severity::Error,
+        }
+    }
+
+    /// Next higher severity, if any, otherwise the severity itself.
+    #[must_use]
+    pub const fn escalate(self) -> Self {
+        match self {
+            Severity::Warning => Severity::Error,
+            Severity::Error | Severity::Fatal => Severity::Fatal,
+        }
+    }
+
+    /// Tracing level for logging reports with the severity.
+    #[must_use]
+    pub const fn level(self) -> tracing::Level {
+        match self {
+            Severity::Warning => tracing::Level::WARN,
+            Severity::Error | Severity::Fatal => tracing::Level::ERROR,
+        }
+    }
+
+    /// Exit code of processes terminating due to a report with the severity.
+    ///
+    /// Warnings do not indicate a failure and, hence, map to `0`.
+    #[must_use]
+    pub const fn exit_code(self) -> u8 {
+        match self {
+            Severity::Warning => 0,
+            Severity::Error => 1,
+            Severity::Fatal => 2,
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Severity;
+    use crate::{Report, ResultExt};
+
+    #[test]
+    fn test_severity() {
+        let report: Report<std::io::Error> = Err::<(), _>(std::io::Error::other("disk full"))
+            .context("writing cache")
+            .expect_err("should fail");
+        assert_eq!(report.severity(), Severity::Error);
+        assert!(report.to_string().starts_with("disk full\n"));
+        let report = report.downgrade();
+        assert_eq!(report.severity(), Severity::Warning);
+        assert!(report.to_string().starts_with("warning: disk full\n"));
+        let report = report.escalate().escalate().escalate();
+        assert_eq!(report.severity(), Severity::Fatal);
+        assert!(report.to_string().starts_with("fatal: disk full\n"));
+        assert_eq!(Severity::Warning.downgrade(), Severity::Warning);
+    }
+}
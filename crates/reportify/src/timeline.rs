@@ -0,0 +1,258 @@
+//! Recording of when and on which thread context items have been attached.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Globally configured switch for recording timestamps.
+#[cfg(feature = "std")]
+static TIMESTAMPS: AtomicBool = AtomicBool::new(Timeline::DEFAULT.timestamps);
+
+/// Globally configured switch for recording threads.
+#[cfg(feature = "std")]
+static THREADS: AtomicBool = AtomicBool::new(Timeline::DEFAULT.threads);
+
+/// Recording of when and on which thread context items have been attached.
+///
+/// When enabled, each context item records the time at which it has been attached
+/// and/or the name and ID of the thread attaching it. The information is rendered
+/// after the location of the item and included in serialized reports, such that the
+/// propagation of an error can be reconstructed as a timeline, e.g., across the
+/// iterations of a retry loop.
+///
+/// Recording is disabled by default and configured globally via [`Timeline::install`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct Timeline {
+    /// Record timestamps.
+    timestamps: bool,
+    /// Record threads.
+    threads: bool,
+}
+
+#[cfg(feature = "std")]
+impl Timeline {
+    /// Default configuration with recording disabled.
+    pub const DEFAULT: Self = Self {
+        timestamps: false,
+        threads: false,
+    };
+
+    /// Configuration with recording timestamps and threads enabled.
+    pub const fn enabled() -> Self {
+        Self {
+            timestamps: true,
+            threads: true,
+        }
+    }
+
+    /// Enable or disable recording timestamps.
+    pub const fn with_timestamps(mut self, timestamps: bool) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Enable or disable recording threads.
+    pub const fn with_threads(mut self, threads: bool) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Whether timestamps are recorded.
+    #[must_use]
+    pub const fn timestamps(&self) -> bool {
+        self.timestamps
+    }
+
+    /// Whether threads are recorded.
+    #[must_use]
+    pub const fn threads(&self) -> bool {
+        self.threads
+    }
+
+    /// Install the configuration globally.
+    pub fn install(self) {
+        TIMESTAMPS.store(self.timestamps, Ordering::Relaxed);
+        THREADS.store(self.threads, Ordering::Relaxed);
+    }
+
+    /// Globally installed configuration.
+    pub fn current() -> Self {
+        Self {
+            timestamps: TIMESTAMPS.load(Ordering::Relaxed),
+            threads: THREADS.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Timeline {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Time and thread at which a context item has been attached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct ItemInfo {
+    /// Milliseconds since the Unix epoch.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    timestamp_ms: Option<u64>,
+    /// Thread which attached the item.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    thread: Option<ThreadInfo>,
+}
+
+impl ItemInfo {
+    /// Capture the information enabled by the installed [`Timeline`], if any.
+    #[cfg(feature = "std")]
+    pub(crate) fn capture() -> Option<Box<Self>> {
+        Self::capture_with(Timeline::current())
+    }
+
+    /// Capture the information enabled by the given [`Timeline`], if any.
+    #[cfg(feature = "std")]
+    fn capture_with(timeline: Timeline) -> Option<Box<Self>> {
+        if !timeline.timestamps && !timeline.threads {
+            return None;
+        }
+        let timestamp_ms = timeline.timestamps.then(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |duration| {
+                    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+                })
+        });
+        let thread = timeline.threads.then(|| {
+            let thread = std::thread::current();
+            ThreadInfo {
+                name: thread.name().map(String::from),
+                id: alloc::format!("{:?}", thread.id()),
+            }
+        });
+        Some(Box::new(Self {
+            timestamp_ms,
+            thread,
+        }))
+    }
+
+    /// Without the `std` feature, no information is captured.
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn capture() -> Option<Box<Self>> {
+        None
+    }
+}
+
+impl Display for ItemInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(timestamp_ms) = self.timestamp_ms {
+            write_timestamp(f, timestamp_ms)?;
+        }
+        if let Some(thread) = &self.thread {
+            if self.timestamp_ms.is_some() {
+                f.write_str(", ")?;
+            }
+            match &thread.name {
+                Some(name) => write!(f, "thread {name}")?,
+                None => write!(f, "thread {}", thread.id)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Name and ID of a thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct ThreadInfo {
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    name: Option<String>,
+    id: String,
+}
+
+/// Write the given Unix timestamp in milliseconds as an RFC 3339 UTC timestamp.
+fn write_timestamp(f: &mut fmt::Formatter<'_>, timestamp_ms: u64) -> fmt::Result {
+    let (days, millis) = (timestamp_ms / 86_400_000, timestamp_ms % 86_400_000);
+    // Convert days since the epoch to a civil date, see
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    write!(
+        f,
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ItemInfo;
+
+    #[test]
+    fn test_render_item_info() {
+        let info = ItemInfo {
+            timestamp_ms: Some(1_709_251_199_999),
+            thread: Some(super::ThreadInfo {
+                name: None,
+                id: "ThreadId(7)".into(),
+            }),
+        };
+        assert_eq!(
+            info.to_string(),
+            "2024-02-29T23:59:59.999Z, thread ThreadId(7)"
+        );
+        let info = ItemInfo {
+            timestamp_ms: Some(0),
+            thread: None,
+        };
+        assert_eq!(info.to_string(), "1970-01-01T00:00:00.000Z");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_timeline() {
+        use super::Timeline;
+        use crate::{Report, ResultExt};
+
+        let mut report: Report<std::io::Error> = Err::<(), _>(std::io::Error::other("disk full"))
+            .context("writing cache")
+            .expect_err("should fail");
+        let entry = report
+            .context
+            .items
+            .last_mut()
+            .expect("should have context item");
+        entry.info = ItemInfo::capture_with(Timeline::DEFAULT.with_threads(true));
+        let rendered = report.to_string();
+        let thread = std::thread::current();
+        let name = thread.name().expect("test thread should be named");
+        assert!(
+            rendered.contains(&format!(" [thread {name}]: writing cache\n")),
+            "{rendered}"
+        );
+    }
+}